@@ -7,12 +7,31 @@ use triad_counter_rs::TriadCounterPlugin;
 /// Generate a random-ish signed adjacency matrix
 fn generate_matrix(n: usize) -> Vec<Vec<f64>> {
     let mut matrix = vec![vec![0.0; n]; n];
-    for i in 0..n {
-        for j in 0..n {
+    for (i, row) in matrix.iter_mut().enumerate() {
+        for (j, cell) in row.iter_mut().enumerate() {
             if i != j {
                 // Deterministic "random" pattern based on indices
                 let val = ((i * 7 + j * 13) % 5) as f64 - 2.0;
-                matrix[i][j] = val;
+                *cell = val;
+            }
+        }
+    }
+    matrix
+}
+
+/// Generate a hub-heavy signed matrix: the first nodes are tied to everyone,
+/// the rest only sparsely, so work per outer index is highly skewed
+fn generate_skewed_matrix(n: usize) -> Vec<Vec<f64>> {
+    let hubs = (n / 50).max(1);
+    let mut matrix = vec![vec![0.0; n]; n];
+    for (i, row) in matrix.iter_mut().enumerate() {
+        for (j, cell) in row.iter_mut().enumerate() {
+            if i == j {
+                continue;
+            }
+            let sign = if (i * 7 + j * 13) % 3 == 0 { -1.0 } else { 1.0 };
+            if i.min(j) < hubs || (i * 31 + j * 17) % 97 == 0 {
+                *cell = sign;
             }
         }
     }
@@ -65,6 +84,75 @@ fn bench_large_networks(c: &mut Criterion) {
                 black_box(counts.total())
             })
         });
+
+        group.bench_with_input(BenchmarkId::new("bitset", size), &plugin, |b, p| {
+            b.iter(|| {
+                let counts = p.count_triads_bitset();
+                black_box(counts.total())
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("simd", size), &plugin, |b, p| {
+            b.iter(|| {
+                let counts = p.count_triads_simd();
+                black_box(counts.total())
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_skewed_networks(c: &mut Criterion) {
+    let mut group = c.benchmark_group("skewed_networks");
+    group.sample_size(20);
+
+    for size in [1000, 2000] {
+        let matrix = generate_skewed_matrix(size);
+        let plugin = TriadCounterPlugin::from_matrix(matrix);
+
+        group.bench_with_input(BenchmarkId::new("sequential", size), &plugin, |b, p| {
+            b.iter(|| {
+                let counts = p.count_triads_sequential();
+                black_box(counts.total())
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("parallel", size), &plugin, |b, p| {
+            b.iter(|| {
+                let counts = p.count_triads_parallel_chunked();
+                black_box(counts.total())
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_csv_input(c: &mut Criterion) {
+    use std::io::Write;
+
+    let mut group = c.benchmark_group("csv_input");
+    group.sample_size(10);
+
+    for size in [500, 2000] {
+        let matrix = generate_matrix(size);
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        let header: Vec<String> = (0..size).map(|i| format!("N{}", i)).collect();
+        writeln!(file, "\"\",{}", header.join(",")).unwrap();
+        for (i, row) in matrix.iter().enumerate() {
+            let cells: Vec<String> = row.iter().map(|v| v.to_string()).collect();
+            writeln!(file, "N{},{}", i, cells.join(",")).unwrap();
+        }
+        file.flush().unwrap();
+
+        group.bench_with_input(BenchmarkId::new("input", size), file.path(), |b, path| {
+            b.iter(|| {
+                let mut plugin = TriadCounterPlugin::new();
+                plugin.input(path).unwrap();
+                black_box(plugin.node_count())
+            })
+        });
     }
 
     group.finish();
@@ -96,6 +184,8 @@ criterion_group!(
     benches,
     bench_triad_counting,
     bench_large_networks,
+    bench_skewed_networks,
+    bench_csv_input,
     bench_full_pipeline
 );
 criterion_main!(benches);
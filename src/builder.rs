@@ -0,0 +1,111 @@
+//! Builder for configuring a [`TriadCounterPlugin`]
+
+use crate::{
+    Config, MissingEdgePolicy, NonFinitePolicy, Symmetrization, SymmetryPolicy, TriadCounterPlugin,
+    TriadCounts, TriadError,
+};
+use std::path::Path;
+
+/// Chainable configuration for loading and counting
+///
+/// Unset options keep the plugin defaults: threshold 0.0, parallel
+/// threshold [`DEFAULT_PARALLEL_THRESHOLD`](crate::DEFAULT_PARALLEL_THRESHOLD)
+/// nodes, comma delimiter, lenient parsing, errors on NaN or
+/// infinite cells, no symmetry check, no symmetrization.
+///
+/// ```no_run
+/// use triad_counter_rs::{Symmetrization, TriadCounterBuilder};
+///
+/// let counts = TriadCounterBuilder::new()
+///     .threshold(0.1)
+///     .delimiter(b'\t')
+///     .symmetrization(Symmetrization::Mean)
+///     .analyze_file("network.tsv")?;
+/// println!("Stable: {}", counts.stable());
+/// # Ok::<(), triad_counter_rs::TriadError>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TriadCounterBuilder {
+    config: Config,
+}
+
+impl TriadCounterBuilder {
+    /// Start from the default configuration
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Treat weights with `|w| <= eps` as no edge
+    pub fn threshold(mut self, eps: f64) -> Self {
+        self.config.threshold = eps;
+        self
+    }
+
+    /// Which weights mean "no edge", e.g. a `-999` sentinel
+    pub fn missing_edge(mut self, policy: MissingEdgePolicy) -> Self {
+        self.config.missing = policy;
+        self
+    }
+
+    /// Use the parallel counter for networks with at least `n` nodes
+    pub fn parallel_threshold(mut self, n: usize) -> Self {
+        self.config.parallel_threshold = n;
+        self
+    }
+
+    /// CSV field delimiter (e.g. `b'\t'` or `b';'`)
+    pub fn delimiter(mut self, delimiter: u8) -> Self {
+        self.config.delimiter = delimiter;
+        self
+    }
+
+    /// Fail on unparseable cells instead of treating them as 0
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.config.strict = strict;
+        self
+    }
+
+    /// Handling of NaN and infinite cells (default: error)
+    pub fn non_finite(mut self, policy: NonFinitePolicy) -> Self {
+        self.config.non_finite = policy;
+        self
+    }
+
+    /// Keep loaded diagonal values instead of zeroing them
+    pub fn keep_diagonal(mut self, keep: bool) -> Self {
+        self.config.keep_diagonal = keep;
+        self
+    }
+
+    /// Keep only edges whose two directions have the same sign
+    pub fn reciprocity_required(mut self, required: bool) -> Self {
+        self.config.reciprocity_required = required;
+        self
+    }
+
+    /// Check or resolve asymmetric input with `policy` while loading
+    pub fn symmetry_policy(mut self, policy: SymmetryPolicy) -> Self {
+        self.config.symmetry_policy = Some(policy);
+        self
+    }
+
+    /// Symmetrize the matrix with `rule` right after loading
+    pub fn symmetrization(mut self, rule: Symmetrization) -> Self {
+        self.config.symmetrization = Some(rule);
+        self
+    }
+
+    /// Create an empty plugin carrying this configuration
+    pub fn build(self) -> TriadCounterPlugin {
+        let mut plugin = TriadCounterPlugin::new();
+        plugin.config = self.config;
+        plugin
+    }
+
+    /// Load a CSV matrix with every configured option, count, and return the counts
+    pub fn analyze_file<P: AsRef<Path>>(self, path: P) -> Result<TriadCounts, TriadError> {
+        let mut plugin = self.build();
+        plugin.input(path)?;
+        Ok(plugin.run().clone())
+    }
+}
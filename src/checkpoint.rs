@@ -0,0 +1,102 @@
+//! Resumable progress of a long triad count
+
+use crate::{TriadCounts, TriadError};
+use std::path::Path;
+
+/// First line of every checkpoint file
+const MAGIC: &str = "# triad-counter checkpoint v1";
+
+/// Partial result of a row-by-row triad count
+///
+/// Row `i` of a count covers exactly the triads whose smallest node is `i`,
+/// so rows are independent: `counts` is the exact total over rows
+/// `0..next_row`, and adding the remaining rows gives the full count. See
+/// [`TriadCounterPlugin::count_triads_checkpointed`](crate::TriadCounterPlugin::count_triads_checkpointed).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CountCheckpoint {
+    /// Nodes in the network being counted
+    pub node_count: usize,
+    /// First row not yet counted; equal to `node_count` when done
+    pub next_row: usize,
+    /// Counts over rows `0..next_row`
+    pub counts: TriadCounts,
+}
+
+impl CountCheckpoint {
+    /// Whether every row has been counted
+    pub fn is_complete(&self) -> bool {
+        self.next_row >= self.node_count
+    }
+
+    /// Write the checkpoint to `path`
+    ///
+    /// The file is written next to `path` and renamed over it, so a crash
+    /// mid-write leaves the previous checkpoint intact.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), TriadError> {
+        let path = path.as_ref();
+        let [three, two, one, zero] = self.counts.as_array();
+        let text = format!(
+            "{}\nnodes={}\nnext_row={}\ncounts={},{},{},{}\n",
+            MAGIC, self.node_count, self.next_row, three, two, one, zero
+        );
+
+        let staging = path.with_extension("partial");
+        std::fs::write(&staging, text)?;
+        std::fs::rename(&staging, path)?;
+        Ok(())
+    }
+
+    /// Read a checkpoint written by [`save`](Self::save)
+    ///
+    /// Fails with [`TriadError::Malformed`] if the file is not a checkpoint.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, TriadError> {
+        let text = std::fs::read_to_string(path)?;
+        let mut lines = text.lines();
+        if lines.next() != Some(MAGIC) {
+            return Err(TriadError::Malformed {
+                line: 1,
+                message: "not a triad-counter checkpoint".to_string(),
+            });
+        }
+
+        let mut field = |line: usize, key: &str| -> Result<&str, TriadError> {
+            lines
+                .next()
+                .and_then(|l| l.strip_prefix(key))
+                .and_then(|l| l.strip_prefix('='))
+                .ok_or_else(|| TriadError::Malformed {
+                    line,
+                    message: format!("expected '{}=' line", key),
+                })
+        };
+        let number = |line: usize, text: &str| -> Result<u64, TriadError> {
+            text.trim().parse().map_err(|_| TriadError::Malformed {
+                line,
+                message: format!("invalid number '{}'", text),
+            })
+        };
+
+        let node_count = number(2, field(2, "nodes")?)? as usize;
+        let next_row = number(3, field(3, "next_row")?)? as usize;
+        let values = field(4, "counts")?
+            .split(',')
+            .map(|v| number(4, v))
+            .collect::<Result<Vec<u64>, _>>()?;
+        let counts: [u64; 4] = values.try_into().map_err(|_| TriadError::Malformed {
+            line: 4,
+            message: "expected four counts".to_string(),
+        })?;
+        if next_row > node_count {
+            return Err(TriadError::Malformed {
+                line: 3,
+                message: format!("next_row {} exceeds {} nodes", next_row, node_count),
+            });
+        }
+
+        Ok(Self {
+            node_count,
+            next_row,
+            counts: TriadCounts::from_array(counts),
+        })
+    }
+}
@@ -0,0 +1,176 @@
+//! Error type for loading and analyzing networks
+
+use std::fmt;
+
+/// Errors produced while reading, validating, or writing networks
+#[derive(Debug)]
+pub enum TriadError {
+    /// Underlying I/O failure
+    Io(std::io::Error),
+    /// Malformed CSV
+    Csv(csv::Error),
+    /// Number of data rows does not match the number of header labels
+    NonSquareMatrix {
+        /// Data rows read
+        rows: usize,
+        /// Node columns declared in the header
+        cols: usize,
+    },
+    /// The matrix is not symmetric and the symmetry policy requires it
+    AsymmetricMatrix {
+        /// Row of the first mismatch (upper triangle)
+        row: usize,
+        /// Column of the first mismatch (upper triangle)
+        col: usize,
+        /// Value at `(row, col)`
+        upper: f64,
+        /// Value at `(col, row)`
+        lower: f64,
+    },
+    /// A flat buffer's length does not match the requested dimensions
+    DimensionMismatch {
+        /// Required number of elements
+        expected: usize,
+        /// Elements supplied
+        found: usize,
+    },
+    /// The input declares no nodes
+    EmptyMatrix,
+    /// A line of a text format could not be interpreted
+    Malformed {
+        /// One-based line number, or 0 for the file as a whole
+        line: usize,
+        /// What was wrong
+        message: String,
+    },
+    /// A format's declared edge count differs from the edges present
+    EdgeCountMismatch {
+        /// Edges declared in the header
+        declared: usize,
+        /// Edges actually read
+        found: usize,
+    },
+    /// A matrix cell could not be parsed as a number (strict parsing only)
+    InvalidValue {
+        /// Zero-based data row
+        row: usize,
+        /// Zero-based node column (excluding the label column)
+        col: usize,
+        /// The offending cell text
+        value: String,
+    },
+    /// A matrix cell holds NaN or an infinity
+    NonFiniteValue {
+        /// Zero-based data row
+        row: usize,
+        /// Zero-based node column
+        col: usize,
+        /// The offending value
+        value: f64,
+    },
+    /// A cell is marked in both the positive and the negative layer
+    ConflictingEdge {
+        /// Label of the cell's row node
+        row: String,
+        /// Label of the cell's column node
+        col: String,
+    },
+    /// The same node label appears more than once in a header
+    DuplicateLabel {
+        /// The repeated label
+        name: String,
+        /// Zero-based node index of the first occurrence
+        first: usize,
+        /// Zero-based node index of the repeat
+        second: usize,
+    },
+    /// The operation reads edge weights, which were freed by
+    /// `TriadCounterPlugin::free_adjacency`
+    WeightsFreed,
+}
+
+impl fmt::Display for TriadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TriadError::Io(e) => write!(f, "I/O error: {}", e),
+            TriadError::Csv(e) => write!(f, "CSV error: {}", e),
+            TriadError::NonSquareMatrix { rows, cols } => write!(
+                f,
+                "matrix is not square: {} data rows for {} labelled columns",
+                rows, cols
+            ),
+            TriadError::AsymmetricMatrix {
+                row,
+                col,
+                upper,
+                lower,
+            } => write!(
+                f,
+                "matrix is not symmetric: ({}, {}) = {} but ({}, {}) = {}",
+                row, col, upper, col, row, lower
+            ),
+            TriadError::DimensionMismatch { expected, found } => {
+                write!(f, "expected {} matrix elements but got {}", expected, found)
+            }
+            TriadError::EmptyMatrix => write!(f, "matrix has no nodes"),
+            TriadError::Malformed { line: 0, message } => write!(f, "malformed input: {}", message),
+            TriadError::Malformed { line, message } => {
+                write!(f, "malformed input at line {}: {}", line, message)
+            }
+            TriadError::EdgeCountMismatch { declared, found } => {
+                write!(f, "expected {} edges but found {}", declared, found)
+            }
+            TriadError::InvalidValue { row, col, value } => {
+                write!(
+                    f,
+                    "invalid value '{}' at row {}, column {}",
+                    value, row, col
+                )
+            }
+            TriadError::NonFiniteValue { row, col, value } => {
+                write!(
+                    f,
+                    "non-finite value {} at row {}, column {}",
+                    value, row, col
+                )
+            }
+            TriadError::ConflictingEdge { row, col } => {
+                write!(f, "edge {}-{} is both positive and negative", row, col)
+            }
+            TriadError::DuplicateLabel {
+                name,
+                first,
+                second,
+            } => write!(
+                f,
+                "duplicate node label '{}' at columns {} and {}",
+                name, first, second
+            ),
+            TriadError::WeightsFreed => {
+                write!(f, "edge weights were freed; reload the network first")
+            }
+        }
+    }
+}
+
+impl std::error::Error for TriadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TriadError::Io(e) => Some(e),
+            TriadError::Csv(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for TriadError {
+    fn from(e: std::io::Error) -> Self {
+        TriadError::Io(e)
+    }
+}
+
+impl From<csv::Error> for TriadError {
+    fn from(e: csv::Error) -> Self {
+        TriadError::Csv(e)
+    }
+}
@@ -12,8 +12,24 @@
 //! - 2 positive, 1 negative edges (two friends are enemies)
 //! - 3 negative edges (all enemies)
 
+use log::{debug, info, warn};
+#[cfg(feature = "parallel")]
 use rayon::prelude::*;
+use std::collections::HashMap;
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+mod builder;
+mod checkpoint;
+mod error;
+mod sparse;
+mod triangular;
+
+pub use builder::TriadCounterBuilder;
+pub use checkpoint::CountCheckpoint;
+pub use error::TriadError;
+pub use sparse::SparseSigns;
+pub use triangular::UpperTriangularSigns;
 
 /// Results from triad counting analysis
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
@@ -47,6 +63,95 @@ impl TriadCounts {
         self.three_positive + self.two_positive + self.one_positive + self.zero_positive
     }
 
+    /// Change from `other` to `self`, per category: positive where `self`
+    /// has more triads
+    pub fn diff(&self, other: &TriadCounts) -> SignedTriadCounts {
+        let [a3, a2, a1, a0] = self.as_array().map(|c| c as i64);
+        let [b3, b2, b1, b0] = other.as_array().map(|c| c as i64);
+        SignedTriadCounts {
+            three_positive: a3 - b3,
+            two_positive: a2 - b2,
+            one_positive: a1 - b1,
+            zero_positive: a0 - b0,
+        }
+    }
+
+    /// Number of triads `theory` considers stable; [`stable`](Self::stable)
+    /// is the [`BalanceTheory::Weak`] case
+    pub fn stable_under(&self, theory: BalanceTheory) -> u64 {
+        match theory {
+            BalanceTheory::Weak => self.stable(),
+            BalanceTheory::Strict => self.three_positive,
+        }
+    }
+
+    /// Number of triads `theory` considers unstable
+    pub fn unstable_under(&self, theory: BalanceTheory) -> u64 {
+        self.total() - self.stable_under(theory)
+    }
+
+    /// Fraction of triads that are stable, or `None` when there are no triads
+    pub fn balance_ratio(&self) -> Option<f64> {
+        let total = self.total();
+        (total > 0).then(|| self.stable() as f64 / total as f64)
+    }
+
+    /// Fraction of triads that are unstable, or `None` when there are no triads
+    pub fn frustration_ratio(&self) -> Option<f64> {
+        self.balance_ratio().map(|ratio| 1.0 - ratio)
+    }
+
+    /// Category fractions `[three, two, one, zero positive] / total()`
+    ///
+    /// All zero when there are no triads.
+    pub fn fractions(&self) -> [f64; 4] {
+        let total = self.total();
+        if total == 0 {
+            return [0.0; 4];
+        }
+        let total = total as f64;
+        self.as_array().map(|count| count as f64 / total)
+    }
+
+    /// The four category counts as
+    /// `[three_positive, two_positive, one_positive, zero_positive]`
+    ///
+    /// This order is part of the stable API, for passing counts across FFI
+    /// boundaries or into array libraries; it matches
+    /// [`fractions`](Self::fractions).
+    #[inline]
+    pub fn as_array(&self) -> [u64; 4] {
+        [
+            self.three_positive,
+            self.two_positive,
+            self.one_positive,
+            self.zero_positive,
+        ]
+    }
+
+    /// Inverse of [`as_array`](Self::as_array), with the same ordering
+    #[inline]
+    pub fn from_array(counts: [u64; 4]) -> Self {
+        let [three_positive, two_positive, one_positive, zero_positive] = counts;
+        TriadCounts {
+            three_positive,
+            two_positive,
+            one_positive,
+            zero_positive,
+        }
+    }
+
+    /// Add one triad of the given type
+    #[inline(always)]
+    fn record(&mut self, triad_type: TriadType) {
+        match triad_type {
+            TriadType::ThreePositive => self.three_positive += 1,
+            TriadType::TwoPositive => self.two_positive += 1,
+            TriadType::OnePositive => self.one_positive += 1,
+            TriadType::ZeroPositive => self.zero_positive += 1,
+        }
+    }
+
     /// Merge counts from another instance
     #[inline]
     fn merge(&mut self, other: &TriadCounts) {
@@ -55,380 +160,6842 @@ impl TriadCounts {
         self.one_positive += other.one_positive;
         self.zero_positive += other.zero_positive;
     }
+
+    /// Remove counts that were previously merged in
+    ///
+    /// Returns `false`, leaving `self` unchanged, if any category of `other`
+    /// exceeds this one, i.e. `other` was never merged in.
+    #[inline]
+    fn unmerge(&mut self, other: &TriadCounts) -> bool {
+        let remaining = (
+            self.three_positive.checked_sub(other.three_positive),
+            self.two_positive.checked_sub(other.two_positive),
+            self.one_positive.checked_sub(other.one_positive),
+            self.zero_positive.checked_sub(other.zero_positive),
+        );
+        match remaining {
+            (Some(three), Some(two), Some(one), Some(zero)) => {
+                self.three_positive = three;
+                self.two_positive = two;
+                self.one_positive = one;
+                self.zero_positive = zero;
+                true
+            }
+            _ => false,
+        }
+    }
 }
 
-/// TriadCounter plugin for PluMA
-pub struct TriadCounterPlugin {
-    /// Adjacency matrix (stored as flat vector for cache efficiency)
-    adj: Vec<f64>,
-    /// Pre-computed sign matrix: 1 = positive, -1 = negative, 0 = zero
-    signs: Vec<i8>,
-    /// Number of nodes
-    n: usize,
-    /// Node labels
-    labels: Vec<String>,
-    /// Computed triad counts
-    counts: TriadCounts,
+/// The summary block of [`TriadCounterPlugin::output`], without the
+/// surrounding rules or a trailing newline:
+///
+/// ```text
+/// Stable triads: 3
+/// Unstable triads: 1
+///
+/// Counts by positive edges:
+/// 3: 2 (50.0%)
+/// 2: 1 (25.0%)
+/// 1: 1 (25.0%)
+/// 0: 0 (0.0%)
+/// ```
+impl std::fmt::Display for TriadCounts {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Percentages of the total; all 0.0% when there are no triads
+        let pct = self.fractions().map(|f| f * 100.0);
+        writeln!(f, "Stable triads: {}", self.stable())?;
+        writeln!(f, "Unstable triads: {}", self.unstable())?;
+        writeln!(f)?;
+        writeln!(f, "Counts by positive edges:")?;
+        writeln!(f, "3: {} ({:.1}%)", self.three_positive, pct[0])?;
+        writeln!(f, "2: {} ({:.1}%)", self.two_positive, pct[1])?;
+        writeln!(f, "1: {} ({:.1}%)", self.one_positive, pct[2])?;
+        write!(f, "0: {} ({:.1}%)", self.zero_positive, pct[3])
+    }
 }
 
-impl TriadCounterPlugin {
-    /// Create a new empty plugin
-    pub fn new() -> Self {
-        Self {
-            adj: Vec::new(),
-            signs: Vec::new(),
-            n: 0,
-            labels: Vec::new(),
-            counts: TriadCounts::default(),
-        }
+/// Signed per-category change between two [`TriadCounts`]; see
+/// [`TriadCounts::diff`]
+///
+/// Supports `+`, `-`, and negation, so changes over several steps can be
+/// combined, e.g. `a.diff(&b) + b.diff(&c) == a.diff(&c)`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct SignedTriadCounts {
+    /// Change in triads with 3 positive edges
+    pub three_positive: i64,
+    /// Change in triads with 2 positive, 1 negative edge
+    pub two_positive: i64,
+    /// Change in triads with 1 positive, 2 negative edges
+    pub one_positive: i64,
+    /// Change in triads with 3 negative edges
+    pub zero_positive: i64,
+}
+
+impl SignedTriadCounts {
+    /// Change in stable triads
+    #[inline]
+    pub fn stable(&self) -> i64 {
+        self.three_positive + self.one_positive
     }
 
-    /// Convert float to sign: 1 = positive, -1 = negative, 0 = zero
-    #[inline(always)]
-    fn to_sign(v: f64) -> i8 {
-        if v > 0.0 {
-            1
-        } else if v < 0.0 {
-            -1
-        } else {
-            0
-        }
+    /// Change in unstable triads
+    #[inline]
+    pub fn unstable(&self) -> i64 {
+        self.two_positive + self.zero_positive
     }
 
-    /// Pre-compute sign matrix for fast access
-    fn compute_signs(&mut self) {
-        self.signs = self.adj.iter().map(|&v| Self::to_sign(v)).collect();
+    /// Change in the total number of triads
+    #[inline]
+    pub fn total(&self) -> i64 {
+        self.stable() + self.unstable()
     }
+}
 
-    /// Load adjacency matrix from CSV file
-    pub fn input<P: AsRef<Path>>(&mut self, path: P) -> Result<(), Box<dyn std::error::Error>> {
-        let mut reader = csv::ReaderBuilder::new()
-            .has_headers(true)
-            .flexible(true)
-            .from_path(path)?;
+impl std::ops::Add for SignedTriadCounts {
+    type Output = Self;
 
-        // Get headers (node labels)
-        let headers = reader.headers()?.clone();
-        self.labels = headers.iter().skip(1).map(|s| s.to_string()).collect();
-        self.n = self.labels.len();
+    fn add(self, other: Self) -> Self {
+        SignedTriadCounts {
+            three_positive: self.three_positive + other.three_positive,
+            two_positive: self.two_positive + other.two_positive,
+            one_positive: self.one_positive + other.one_positive,
+            zero_positive: self.zero_positive + other.zero_positive,
+        }
+    }
+}
 
-        // Pre-allocate adjacency matrix
-        self.adj = vec![0.0; self.n * self.n];
+impl std::ops::Neg for SignedTriadCounts {
+    type Output = Self;
 
-        // Read matrix rows
-        for (row_idx, result) in reader.records().enumerate() {
-            let record = result?;
-            for (col_idx, field) in record.iter().skip(1).enumerate() {
-                if col_idx < self.n {
-                    let value: f64 = field.trim().parse().unwrap_or(0.0);
-                    self.adj[row_idx * self.n + col_idx] = value;
-                }
-            }
+    fn neg(self) -> Self {
+        SignedTriadCounts {
+            three_positive: -self.three_positive,
+            two_positive: -self.two_positive,
+            one_positive: -self.one_positive,
+            zero_positive: -self.zero_positive,
         }
+    }
+}
 
-        // Zero diagonal
-        for i in 0..self.n {
-            self.adj[i * self.n + i] = 0.0;
-        }
+impl std::ops::Sub for SignedTriadCounts {
+    type Output = Self;
 
-        // Pre-compute signs
-        self.compute_signs();
+    fn sub(self, other: Self) -> Self {
+        self + -other
+    }
+}
 
-        Ok(())
+/// `+42 stable, -17 unstable`
+impl std::fmt::Display for SignedTriadCounts {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:+} stable, {:+} unstable",
+            self.stable(),
+            self.unstable()
+        )
     }
+}
 
-    /// Count triads - automatically chooses best strategy
-    pub fn run(&mut self) {
-        if self.signs.is_empty() {
-            self.compute_signs();
-        }
-        self.counts = self.count_triads_optimized();
+/// Euclidean distance between the triad profiles of two networks
+///
+/// Each profile is the vector of category proportions (see
+/// [`TriadCounterPlugin::triad_profile_vector`]), so networks of different
+/// sizes are directly comparable. The distance lies in `[0, sqrt(2)]`.
+pub fn profile_distance(a: &TriadCounts, b: &TriadCounts) -> f64 {
+    a.fractions()
+        .iter()
+        .zip(b.fractions())
+        .map(|(x, y)| (x - y) * (x - y))
+        .sum::<f64>()
+        .sqrt()
+}
+
+/// Serializes the four category counts plus the derived `stable` and
+/// `unstable` totals, so consumers don't recompute them
+#[cfg(feature = "serde")]
+impl serde::Serialize for TriadCounts {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("TriadCounts", 6)?;
+        state.serialize_field("three_positive", &self.three_positive)?;
+        state.serialize_field("two_positive", &self.two_positive)?;
+        state.serialize_field("one_positive", &self.one_positive)?;
+        state.serialize_field("zero_positive", &self.zero_positive)?;
+        state.serialize_field("stable", &self.stable())?;
+        state.serialize_field("unstable", &self.unstable())?;
+        state.end()
     }
+}
 
-    /// Optimized triad counting using pre-computed signs
-    pub fn count_triads_optimized(&self) -> TriadCounts {
-        // Use parallel only for large networks (>500 nodes = 20M+ triads)
-        if self.n >= 500 {
-            self.count_triads_parallel_chunked()
-        } else {
-            self.count_triads_sequential()
+/// Write `counts` as the last member of a JSON object: a `"counts"` object
+/// with the four categories plus the derived `stable` and `unstable` totals
+fn write_counts_json<W: std::io::Write>(
+    writer: &mut W,
+    counts: &TriadCounts,
+) -> std::io::Result<()> {
+    writeln!(writer, "  \"counts\": {{")?;
+    writeln!(writer, "    \"three_positive\": {},", counts.three_positive)?;
+    writeln!(writer, "    \"two_positive\": {},", counts.two_positive)?;
+    writeln!(writer, "    \"one_positive\": {},", counts.one_positive)?;
+    writeln!(writer, "    \"zero_positive\": {},", counts.zero_positive)?;
+    writeln!(writer, "    \"stable\": {},", counts.stable())?;
+    writeln!(writer, "    \"unstable\": {}", counts.unstable())?;
+    writeln!(writer, "  }}")
+}
+
+/// Quote and escape a string for JSON output
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
         }
     }
+    out.push('"');
+    out
+}
 
-    /// Sequential triad counting with pre-computed signs
-    pub fn count_triads_sequential(&self) -> TriadCounts {
-        let mut counts = TriadCounts::default();
-        let n = self.n;
+/// Open an input file, transparently decompressing gzip when the `gzip`
+/// feature is enabled and the file has a `.gz` extension or gzip magic bytes
+fn open_input<P: AsRef<Path>>(path: P) -> Result<Box<dyn std::io::Read>, TriadError> {
+    let file = std::io::BufReader::new(std::fs::File::open(path.as_ref())?);
 
-        for i in 0..n {
-            let i_offset = i * n;
-            for j in (i + 1)..n {
-                let ij = self.signs[i_offset + j];
-                // Skip if no edge between i and j
-                if ij == 0 {
-                    continue;
-                }
+    #[cfg(feature = "gzip")]
+    {
+        use std::io::BufRead;
 
-                let j_offset = j * n;
-                for k in (j + 1)..n {
-                    let ik = self.signs[i_offset + k];
-                    let jk = self.signs[j_offset + k];
+        let mut file = file;
+        let has_gz_extension = path.as_ref().extension().is_some_and(|ext| ext == "gz");
+        let has_gz_magic = file.fill_buf()?.starts_with(&[0x1f, 0x8b]);
+        if has_gz_extension || has_gz_magic {
+            return Ok(Box::new(flate2::read::GzDecoder::new(file)));
+        }
+        Ok(Box::new(file))
+    }
 
-                    // Skip if missing edges
-                    if ik == 0 || jk == 0 {
-                        continue;
-                    }
+    #[cfg(not(feature = "gzip"))]
+    Ok(Box::new(file))
+}
 
-                    // Count positive edges: sign > 0 gives 1, else 0
-                    let pos_count = ((ij > 0) as u8) + ((ik > 0) as u8) + ((jk > 0) as u8);
+/// Lines starting with this byte are skipped by the CSV readers
+const COMMENT_PREFIX: u8 = b'#';
 
-                    match pos_count {
-                        3 => counts.three_positive += 1,
-                        2 => counts.two_positive += 1,
-                        1 => counts.one_positive += 1,
-                        0 => counts.zero_positive += 1,
-                        _ => {}
-                    }
-                }
-            }
-        }
+/// Read the node labels from the header of a comma-separated CSV matrix
+///
+/// Only the header row is read, so this is cheap even for huge files. Labels
+/// are returned as [`TriadCounterPlugin::input`] would load them, without the
+/// duplicate check; gzip input is decompressed with the `gzip` feature. Fails
+/// with [`TriadError::EmptyMatrix`] if the header names no nodes.
+pub fn read_labels<P: AsRef<Path>>(path: P) -> Result<Vec<String>, TriadError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(true)
+        .flexible(true)
+        .comment(Some(COMMENT_PREFIX))
+        .from_reader(open_input(path)?);
+    let labels: Vec<String> = reader
+        .headers()?
+        .iter()
+        .skip(1)
+        .map(|s| s.to_string())
+        .collect();
+    if labels.is_empty() {
+        return Err(TriadError::EmptyMatrix);
+    }
+    Ok(labels)
+}
 
-        counts
+/// Number of possible triads among `n` nodes: C(n, 3), saturating at
+/// `u64::MAX`
+#[inline]
+fn choose3(n: usize) -> u64 {
+    if n < 3 {
+        return 0;
     }
 
-    /// Parallel triad counting with chunked workload
-    pub fn count_triads_parallel_chunked(&self) -> TriadCounts {
-        let n = self.n;
+    let n = n as u128;
+    let mut factors = [n, n - 1, n - 2];
+    // Of three consecutive integers one is a multiple of 3 and one of 2
+    for divisor in [3, 2] {
+        let f = factors
+            .iter_mut()
+            .find(|f| **f % divisor == 0)
+            .expect("consecutive integers contain every small factor");
+        *f /= divisor;
+    }
+
+    factors
+        .iter()
+        .try_fold(1u128, |product, &f| product.checked_mul(f))
+        .and_then(|count| u64::try_from(count).ok())
+        .unwrap_or(u64::MAX)
+}
 
-        // Create chunks of approximately equal work
-        // Work for row i = sum from j=i+1 to n-1 of (n-1-j) = (n-i-1)(n-i-2)/2
-        // We'll parallelize over i with appropriate granularity
+/// Positive-edge fractions outside this range make balance metrics near-deterministic
+pub const SKEWED_POSITIVE_FRACTION: (f64, f64) = (0.05, 0.95);
 
-        (0..n)
-            .into_par_iter()
-            .fold(TriadCounts::default, |mut counts, i| {
-                let i_offset = i * n;
-                for j in (i + 1)..n {
-                    let ij = self.signs[i_offset + j];
-                    if ij == 0 {
-                        continue;
-                    }
+/// Edge densities below this are counted and stored sparsely
+pub const SPARSE_DENSITY: f64 = 0.05;
 
-                    let j_offset = j * n;
-                    for k in (j + 1)..n {
-                        let ik = self.signs[i_offset + k];
-                        let jk = self.signs[j_offset + k];
+/// Node count from which counts run in parallel unless configured otherwise
+/// (20M+ candidate triads)
+pub const DEFAULT_PARALLEL_THRESHOLD: usize = 500;
 
-                        if ik == 0 || jk == 0 {
-                            continue;
-                        }
+/// Minimum `(i, j)` pairs per parallel task, keeping splitting overhead low
+#[cfg(feature = "parallel")]
+const PAIRS_PER_TASK: usize = 16;
 
-                        let pos_count = ((ij > 0) as u8) + ((ik > 0) as u8) + ((jk > 0) as u8);
+/// Number of bits set in both of two equal-length bitsets
+#[inline(always)]
+fn and_popcount(a: &[u64], b: &[u64]) -> u64 {
+    a.iter()
+        .zip(b)
+        .map(|(x, y)| (x & y).count_ones() as u64)
+        .sum()
+}
 
-                        match pos_count {
-                            3 => counts.three_positive += 1,
-                            2 => counts.two_positive += 1,
-                            1 => counts.one_positive += 1,
-                            0 => counts.zero_positive += 1,
-                            _ => {}
-                        }
-                    }
-                }
-                counts
-            })
+/// Sum the per-row counts `row(0) .. row(n - 1)`
+///
+/// Rows are spread over rayon's pool when `parallel` is set and the `parallel`
+/// feature is enabled, and summed in order on the calling thread otherwise.
+pub(crate) fn sum_rows<F>(n: usize, parallel: bool, row: F) -> TriadCounts
+where
+    F: Fn(usize) -> TriadCounts + Sync + Send,
+{
+    #[cfg(feature = "parallel")]
+    if parallel {
+        return (0..n)
+            .into_par_iter()
+            .map(row)
             .reduce(TriadCounts::default, |mut a, b| {
                 a.merge(&b);
                 a
-            })
+            });
     }
+    #[cfg(not(feature = "parallel"))]
+    let _ = parallel;
 
-    /// Write results to output file
-    pub fn output<P: AsRef<Path>>(&self, path: P) -> Result<(), Box<dyn std::error::Error>> {
-        use std::fs::File;
-        use std::io::Write;
+    let mut counts = TriadCounts::default();
+    for i in 0..n {
+        counts.merge(&row(i));
+    }
+    counts
+}
 
-        let mut file = File::create(path)?;
+/// Third nodes classified per step of the vectorized inner loop
+const SIMD_LANES: usize = 32;
 
-        writeln!(file, "*********************************************")?;
-        writeln!(file, "Stable triads: {}", self.counts.stable())?;
-        writeln!(file, "Unstable triads: {}", self.counts.unstable())?;
-        writeln!(file)?;
-        writeln!(file, "Counts by positive edges:")?;
-        writeln!(file, "3: {}", self.counts.three_positive)?;
-        writeln!(file, "2: {}", self.counts.two_positive)?;
-        writeln!(file, "1: {}", self.counts.one_positive)?;
-        writeln!(file, "0: {}", self.counts.zero_positive)?;
-        writeln!(file, "*********************************************")?;
+/// Classify third nodes by the signs of their two edges, as
+/// `(both_pos, mixed, both_neg)`; lanes where either edge is absent count in
+/// none. Branch-free, so fixed-width chunks compile to vector compares.
+#[inline(always)]
+fn classify_lanes(ik: &[i8], jk: &[i8]) -> (u64, u64, u64) {
+    let lanes = || ik.iter().zip(jk).map(|(&x, &y)| (x, y));
+    let both_pos = lanes().map(|(x, y)| ((x > 0) & (y > 0)) as u64).sum();
+    let mixed = lanes()
+        .map(|(x, y)| (((x > 0) & (y < 0)) | ((x < 0) & (y > 0))) as u64)
+        .sum();
+    let both_neg = lanes().map(|(x, y)| ((x < 0) & (y < 0)) as u64).sum();
+    (both_pos, mixed, both_neg)
+}
 
-        Ok(())
+/// Vectorized form of [`count_signs_pair`]: third nodes `k > j` are
+/// classified [`SIMD_LANES`] at a time
+#[inline]
+fn count_signs_pair_simd(signs: &[i8], n: usize, i: usize, j: usize, counts: &mut TriadCounts) {
+    let ij = signs[i * n + j];
+    if ij == 0 {
+        return;
     }
 
-    /// Get the computed triad counts
-    pub fn counts(&self) -> &TriadCounts {
-        &self.counts
+    let ik = &signs[i * n + j + 1..(i + 1) * n];
+    let jk = &signs[j * n + j + 1..(j + 1) * n];
+    let (mut both_pos, mut mixed, mut both_neg) = (0, 0, 0);
+    let mut add = |(p, m, q): (u64, u64, u64)| {
+        both_pos += p;
+        mixed += m;
+        both_neg += q;
+    };
+
+    let (ik_chunks, jk_chunks) = (ik.chunks_exact(SIMD_LANES), jk.chunks_exact(SIMD_LANES));
+    let (ik_rest, jk_rest) = (ik_chunks.remainder(), jk_chunks.remainder());
+    for (a, b) in ik_chunks.zip(jk_chunks) {
+        add(classify_lanes(a, b));
     }
+    add(classify_lanes(ik_rest, jk_rest));
 
-    /// Get number of nodes
-    pub fn node_count(&self) -> usize {
-        self.n
+    if ij > 0 {
+        counts.three_positive += both_pos;
+        counts.two_positive += mixed;
+        counts.one_positive += both_neg;
+    } else {
+        counts.two_positive += both_pos;
+        counts.one_positive += mixed;
+        counts.zero_positive += both_neg;
     }
+}
 
-    /// Get node labels
-    pub fn labels(&self) -> &[String] {
-        &self.labels
+/// Count triads over a flat row-major `n * n` sign matrix, sequentially
+///
+/// Each entry is 1, -1, or 0 (no edge), and only the upper triangle
+/// `signs[i * n + j]` with `i < j` is read. This is the algorithm behind
+/// [`TriadCounterPlugin::count_triads_sequential`], usable on signs from any
+/// source without building a plugin.
+///
+/// # Panics
+///
+/// Panics if `signs.len() != n * n`.
+pub fn count_triads_from_signs(signs: &[i8], n: usize) -> TriadCounts {
+    assert_eq!(signs.len(), n * n, "expected an {0} x {0} sign matrix", n);
+    let mut counts = TriadCounts::default();
+    for i in 0..n {
+        count_signs_row(signs, n, i, &mut counts);
     }
+    counts
+}
 
-    /// Create plugin from adjacency matrix directly (for testing/benchmarking)
-    pub fn from_matrix(matrix: Vec<Vec<f64>>) -> Self {
-        let n = matrix.len();
-        let mut adj = vec![0.0; n * n];
+/// Parallel form of [`count_triads_from_signs`], splitting work over
+/// `(i, j)` pairs as in [`TriadCounterPlugin::count_triads_parallel_chunked`]
+///
+/// Without the `parallel` feature this is the same as
+/// [`count_triads_from_signs`].
+///
+/// # Panics
+///
+/// Panics if `signs.len() != n * n`.
+pub fn count_triads_from_signs_parallel(signs: &[i8], n: usize) -> TriadCounts {
+    #[cfg(feature = "parallel")]
+    {
+        assert_eq!(signs.len(), n * n, "expected an {0} x {0} sign matrix", n);
+        let merge = |mut a: TriadCounts, b: TriadCounts| {
+            a.merge(&b);
+            a
+        };
 
-        for (i, row) in matrix.iter().enumerate() {
-            for (j, &val) in row.iter().enumerate() {
-                if i != j {
-                    adj[i * n + j] = val;
+        (0..n)
+            .into_par_iter()
+            .map(|i| {
+                ((i + 1)..n)
+                    .into_par_iter()
+                    .with_min_len(PAIRS_PER_TASK)
+                    .fold(TriadCounts::default, |mut counts, j| {
+                        count_signs_pair(signs, n, i, j, &mut counts);
+                        counts
+                    })
+                    .reduce(TriadCounts::default, merge)
+            })
+            .reduce(TriadCounts::default, merge)
+    }
+    #[cfg(not(feature = "parallel"))]
+    count_triads_from_signs(signs, n)
+}
+
+/// Add the triads whose smallest node is `i` to `counts`
+#[inline]
+fn count_signs_row(signs: &[i8], n: usize, i: usize, counts: &mut TriadCounts) {
+    for j in (i + 1)..n {
+        count_signs_pair(signs, n, i, j, counts);
+    }
+}
+
+/// Add the triads whose two smallest nodes are `i < j` to `counts`
+#[inline]
+fn count_signs_pair(signs: &[i8], n: usize, i: usize, j: usize, counts: &mut TriadCounts) {
+    let i_offset = i * n;
+    let ij = signs[i_offset + j];
+    // Skip if no edge between i and j
+    if ij == 0 {
+        return;
+    }
+
+    let j_offset = j * n;
+    for k in (j + 1)..n {
+        let ik = signs[i_offset + k];
+        let jk = signs[j_offset + k];
+
+        // Skip if missing edges
+        if ik == 0 || jk == 0 {
+            continue;
+        }
+
+        // Count positive edges: sign > 0 gives 1, else 0
+        let pos_count = ((ij > 0) as u8) + ((ik > 0) as u8) + ((jk > 0) as u8);
+        counts.record(TriadType::from_positive_count(pos_count));
+    }
+}
+
+/// Count triads over `n` nodes using a caller-provided sign function
+///
+/// `sign_of(i, j)` is only called with `i < j` and should return 1, -1, or 0
+/// (no edge); any other value counts as its sign. Nothing is stored, so signs
+/// may be computed lazily from any source. The closure is called roughly
+/// `n³ / 3` times, including repeated calls for the same pair, so expensive
+/// sign computations should be cached by the caller or precomputed into a
+/// matrix. Networks with [`DEFAULT_PARALLEL_THRESHOLD`] or more nodes are
+/// counted in parallel when the `parallel` feature is enabled.
+pub fn count_triads_with_sign_fn<F: Fn(usize, usize) -> i8 + Sync>(
+    n: usize,
+    sign_of: F,
+) -> TriadCounts {
+    let row = |i: usize| {
+        let mut counts = TriadCounts::default();
+        for j in (i + 1)..n {
+            let ij = sign_of(i, j);
+            if ij == 0 {
+                continue;
+            }
+            for k in (j + 1)..n {
+                let ik = sign_of(i, k);
+                if ik == 0 {
+                    continue;
+                }
+                let jk = sign_of(j, k);
+                if jk == 0 {
+                    continue;
                 }
+                let pos_count = ((ij > 0) as u8) + ((ik > 0) as u8) + ((jk > 0) as u8);
+                counts.record(TriadType::from_positive_count(pos_count));
             }
         }
+        counts
+    };
 
-        let signs = adj.iter().map(|&v| Self::to_sign(v)).collect();
+    sum_rows(n, n >= DEFAULT_PARALLEL_THRESHOLD, row)
+}
 
-        Self {
-            adj,
-            signs,
-            n,
-            labels: (0..n).map(|i| format!("Node{}", i)).collect(),
-            counts: TriadCounts::default(),
-        }
-    }
+/// Which triads a balance theory calls stable
+///
+/// The theories differ only on the one-positive triad, two friends with a
+/// common enemy. Both call the all-positive triad stable and the
+/// two-positive and all-negative triads unstable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BalanceTheory {
+    /// Balance as in Easley and Kleinberg, and what [`TriadCounts::stable`]
+    /// and [`TriadType::is_stable`] use: triads with 3 or 1 positive edges
+    /// are stable, since the enemy of my enemy is my friend
+    ///
+    /// Stable: [`ThreePositive`](TriadType::ThreePositive) and
+    /// [`OnePositive`](TriadType::OnePositive); unstable:
+    /// [`TwoPositive`](TriadType::TwoPositive) and
+    /// [`ZeroPositive`](TriadType::ZeroPositive). Unlike Davis's (1967) weak
+    /// balance, the all-negative triad stays unstable.
+    #[default]
+    Weak,
+    /// Only the all-positive triad is stable; the one-positive triad is
+    /// unstable too, as an alliance against a third party rather than a
+    /// balanced group
+    ///
+    /// Stable: [`ThreePositive`](TriadType::ThreePositive) only; unstable:
+    /// the other three types.
+    Strict,
 }
 
-impl Default for TriadCounterPlugin {
-    fn default() -> Self {
-        Self::new()
-    }
+/// Classification of a complete triad by its number of positive edges
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TriadType {
+    /// 3 positive edges (all friends)
+    ThreePositive,
+    /// 2 positive, 1 negative edge
+    TwoPositive,
+    /// 1 positive, 2 negative edges
+    OnePositive,
+    /// 3 negative edges (all enemies)
+    ZeroPositive,
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::io::Write;
-    use tempfile::NamedTempFile;
+impl TriadType {
+    /// Whether balance theory considers this configuration stable
+    /// (3 or 1 positive edges)
+    #[inline]
+    pub fn is_stable(self) -> bool {
+        self.is_stable_under(BalanceTheory::Weak)
+    }
 
-    fn create_test_csv(content: &str) -> NamedTempFile {
-        let mut file = NamedTempFile::new().unwrap();
-        write!(file, "{}", content).unwrap();
-        file
+    /// Whether `theory` considers this configuration stable
+    #[inline]
+    pub fn is_stable_under(self, theory: BalanceTheory) -> bool {
+        match self {
+            TriadType::ThreePositive => true,
+            TriadType::OnePositive => theory == BalanceTheory::Weak,
+            TriadType::TwoPositive | TriadType::ZeroPositive => false,
+        }
     }
 
-    #[test]
-    fn test_all_positive_triad() {
-        // 3 nodes, all positive edges -> 1 stable triad (3 positive)
-        let matrix = vec![
-            vec![0.0, 1.0, 1.0],
-            vec![1.0, 0.0, 1.0],
-            vec![1.0, 1.0, 0.0],
-        ];
-        let mut plugin = TriadCounterPlugin::from_matrix(matrix);
-        plugin.run();
+    /// Number of positive edges in the triad (0..=3)
+    #[inline]
+    pub fn positive_edges(self) -> u8 {
+        match self {
+            TriadType::ThreePositive => 3,
+            TriadType::TwoPositive => 2,
+            TriadType::OnePositive => 1,
+            TriadType::ZeroPositive => 0,
+        }
+    }
 
-        assert_eq!(plugin.counts().three_positive, 1);
-        assert_eq!(plugin.counts().stable(), 1);
-        assert_eq!(plugin.counts().unstable(), 0);
+    /// Classify from the number of positive edges (0..=3)
+    #[inline(always)]
+    fn from_positive_count(pos_count: u8) -> Self {
+        match pos_count {
+            3 => TriadType::ThreePositive,
+            2 => TriadType::TwoPositive,
+            1 => TriadType::OnePositive,
+            _ => TriadType::ZeroPositive,
+        }
     }
+}
 
-    #[test]
+/// Ordering for per-node reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortKey {
+    /// Most triads first
+    TotalTriads,
+    /// Most unstable triads first
+    UnstableTriads,
+    /// Alphabetical by node label
+    Label,
+}
+
+/// Rule for making an asymmetric adjacency matrix symmetric
+///
+/// Each rule combines the two directed values `a = adj[i][j]` and
+/// `b = adj[j][i]` (with `i < j`) into one value stored in both directions.
+/// The variant docs say how a sign conflict (one positive, one negative
+/// direction) is resolved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Symmetrization {
+    /// Keep the upper-triangle value `a`
+    Upper,
+    /// Keep the lower-triangle value `b`
+    Lower,
+    /// Average `(a + b) / 2`; opposite signs of equal magnitude cancel to no
+    /// edge, otherwise the stronger direction's sign survives, weakened
+    Mean,
+    /// Larger value, so a positive direction wins over a negative one
+    Max,
+    /// Smaller value, so a negative direction wins over a positive one
+    Min,
+    /// Value of larger magnitude, so the edge is present if either direction
+    /// is; in a conflict the stronger direction wins, and `a` on a tie
+    Or,
+}
+
+/// Former name of [`Symmetrization`]
+#[deprecated(note = "use Symmetrization")]
+pub type SymmetrizeMode = Symmetrization;
+
+impl Symmetrization {
+    /// Combine the upper (`a`) and lower (`b`) directed values
+    #[inline]
+    fn combine(self, a: f64, b: f64) -> f64 {
+        match self {
+            Symmetrization::Upper => a,
+            Symmetrization::Lower => b,
+            Symmetrization::Mean => (a + b) / 2.0,
+            Symmetrization::Max => a.max(b),
+            Symmetrization::Min => a.min(b),
+            Symmetrization::Or => {
+                if b.abs() > a.abs() {
+                    b
+                } else {
+                    a
+                }
+            }
+        }
+    }
+}
+
+/// How loading handles a matrix where `adj[i][j] != adj[j][i]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymmetryPolicy {
+    /// Fail with [`TriadError::AsymmetricMatrix`] at the first mismatch
+    RequireSymmetric,
+    /// Mirror the upper triangle onto the lower one
+    UseUpper,
+    /// Mirror the lower triangle onto the upper one (for half-exported matrices)
+    UseLower,
+    /// Replace both directions with their mean
+    Average,
+}
+
+impl SymmetryPolicy {
+    /// Symmetrization rule implementing this policy, if it rewrites the matrix
+    fn rule(self) -> Option<Symmetrization> {
+        match self {
+            SymmetryPolicy::RequireSymmetric => None,
+            SymmetryPolicy::UseUpper => Some(Symmetrization::Upper),
+            SymmetryPolicy::UseLower => Some(Symmetrization::Lower),
+            SymmetryPolicy::Average => Some(Symmetrization::Mean),
+        }
+    }
+}
+
+/// How loading handles NaN and infinite matrix values
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NonFinitePolicy {
+    /// Fail with [`TriadError::NonFiniteValue`] at the first such cell
+    #[default]
+    Error,
+    /// Treat such cells as no edge, logging how many were replaced
+    Zero,
+}
+
+/// Which stored weights mean "no edge"
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum MissingEdgePolicy {
+    /// Weights within the threshold of zero are absent (the default)
+    #[default]
+    Zero,
+    /// Only weights equal to the sentinel are absent; every other weight is an
+    /// edge, positive above the threshold and negative otherwise, so a literal
+    /// 0.0 is a negative tie
+    Sentinel(f64),
+}
+
+impl MissingEdgePolicy {
+    /// Weight that encodes an absent edge under this policy
+    fn absent_value(self) -> f64 {
+        match self {
+            MissingEdgePolicy::Zero => 0.0,
+            MissingEdgePolicy::Sentinel(missing) => missing,
+        }
+    }
+}
+
+/// Memory layout for a network's signs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Storage {
+    /// Full `n * n` matrices, as held by [`TriadCounterPlugin`]; works for
+    /// any input and keeps the weights
+    Full,
+    /// Upper triangle only, as held by [`UpperTriangularSigns`]; half the
+    /// memory, for symmetric networks
+    UpperTriangular,
+    /// Forward neighbor lists, as held by [`SparseSigns`]; memory grows with
+    /// the edges, for symmetric networks below [`SPARSE_DENSITY`]
+    Sparse,
+}
+
+/// Counting strategy selected by [`TriadCounterPlugin::count_triads_optimized`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CountingStrategy {
+    /// Single-threaded loop
+    Sequential,
+    /// Rayon-parallel loop over the outer node index
+    Parallel,
+    /// Neighbor-list intersection over a [`SparseSigns`] copy, for networks
+    /// below [`SPARSE_DENSITY`]
+    Sparse,
+}
+
+impl CountingStrategy {
+    /// Lowercase name for reports
+    pub fn name(self) -> &'static str {
+        match self {
+            CountingStrategy::Sequential => "sequential",
+            CountingStrategy::Parallel => "parallel",
+            CountingStrategy::Sparse => "sparse",
+        }
+    }
+}
+
+/// Lazy iterator over complete triads; see [`TriadCounterPlugin::enumerate_triads`]
+struct TriadIter<'a> {
+    signs: &'a [i8],
+    n: usize,
+    i: usize,
+    j: usize,
+    k: usize,
+}
+
+impl Iterator for TriadIter<'_> {
+    type Item = (usize, usize, usize, u8);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let n = self.n;
+        loop {
+            if self.i + 2 >= n {
+                return None;
+            }
+            if self.k >= n {
+                // Advance to the next (i, j) pair
+                self.j += 1;
+                if self.j + 1 >= n {
+                    self.i += 1;
+                    self.j = self.i + 1;
+                }
+                self.k = self.j + 1;
+                continue;
+            }
+
+            let (i, j, k) = (self.i, self.j, self.k);
+            let ij = self.signs[i * n + j];
+            if ij == 0 {
+                self.k = n;
+                continue;
+            }
+            self.k += 1;
+
+            let ik = self.signs[i * n + k];
+            let jk = self.signs[j * n + k];
+            if ik == 0 || jk == 0 {
+                continue;
+            }
+
+            let pos_count = ((ij > 0) as u8) + ((ik > 0) as u8) + ((jk > 0) as u8);
+            return Some((i, j, k, pos_count));
+        }
+    }
+}
+
+/// Triad totals accumulated with real-valued weights instead of unit counts
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WeightedTriadCounts {
+    /// Weight of triads with 3 positive edges
+    pub three_positive: f64,
+    /// Weight of triads with 2 positive, 1 negative edge
+    pub two_positive: f64,
+    /// Weight of triads with 1 positive, 2 negative edges
+    pub one_positive: f64,
+    /// Weight of triads with 3 negative edges
+    pub zero_positive: f64,
+}
+
+impl WeightedTriadCounts {
+    /// Add `weight` to the category of one triad
+    #[inline(always)]
+    fn record(&mut self, triad_type: TriadType, weight: f64) {
+        match triad_type {
+            TriadType::ThreePositive => self.three_positive += weight,
+            TriadType::TwoPositive => self.two_positive += weight,
+            TriadType::OnePositive => self.one_positive += weight,
+            TriadType::ZeroPositive => self.zero_positive += weight,
+        }
+    }
+
+    /// Weight of stable triads (3 positive or 1 positive)
+    #[inline]
+    pub fn stable(&self) -> f64 {
+        self.three_positive + self.one_positive
+    }
+
+    /// Weight of unstable triads (2 positive or 0 positive)
+    #[inline]
+    pub fn unstable(&self) -> f64 {
+        self.two_positive + self.zero_positive
+    }
+
+    /// Total weight over all triads
+    #[inline]
+    pub fn total(&self) -> f64 {
+        self.three_positive + self.two_positive + self.one_positive + self.zero_positive
+    }
+}
+
+/// Edge-sign overview of a loaded network, each undirected pair counted once
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NetworkSummary {
+    /// Pairs with a positive edge
+    pub positive_edges: u64,
+    /// Pairs with a negative edge
+    pub negative_edges: u64,
+    /// Pairs with no edge
+    pub zero_entries: u64,
+    /// Present edges over possible pairs, `edges / (n * (n - 1) / 2)`;
+    /// 0.0 for fewer than 2 nodes
+    pub density: f64,
+}
+
+impl NetworkSummary {
+    /// Number of present edges of either sign
+    #[inline]
+    pub fn edges(&self) -> u64 {
+        self.positive_edges + self.negative_edges
+    }
+}
+
+/// Pre-count estimate of the work a triad count will do; see
+/// [`TriadCounterPlugin::estimate_work`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WorkEstimate {
+    /// Candidate triangles, `C(n, 3)`
+    pub candidate_triads: u64,
+    /// Present edges, each undirected pair counted once
+    pub edges: u64,
+    /// Fraction of `(i, j)` pairs the counting loop skips outright because
+    /// the pair has no edge
+    pub skip_rate: f64,
+    /// Third-node checks the counting loop will make: for every present pair
+    /// `i < j`, one per `k > j`. Runtime scales with this number.
+    pub inner_checks: u64,
+    /// Complete triads expected if edges were spread uniformly at the
+    /// observed density, `C(n, 3) * density³`
+    pub expected_triads: f64,
+}
+
+/// Expected (model-based) triad counts, which need not be whole numbers
+pub type ExpectedTriadCounts = WeightedTriadCounts;
+
+/// How [`TriadCounterPlugin::count_triads_imputed`] fills in absent edges
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImputationMethod {
+    /// Give an absent pair the majority sign among both endpoints' ties to
+    /// their shared neighbors; ties in the vote leave the pair absent
+    NeighborMajority,
+    /// Make an absent pair positive with probability equal to the positive
+    /// fraction of both endpoints' ties to their shared neighbors
+    NeighborFrequency,
+}
+
+/// Expected counts over the upper triangle, given for each pair the
+/// probability that it is present and the probability it is positive
+/// when present; pairs are treated as independent
+fn expected_counts_from_probabilities(
+    n: usize,
+    presence: &[f64],
+    positive: &[f64],
+) -> ExpectedTriadCounts {
+    let mut expected = ExpectedTriadCounts::default();
+
+    for i in 0..n {
+        let i_offset = i * n;
+        for j in (i + 1)..n {
+            let pres_ij = presence[i_offset + j];
+            if pres_ij == 0.0 {
+                continue;
+            }
+            let j_offset = j * n;
+            for k in (j + 1)..n {
+                let closed = pres_ij * presence[i_offset + k] * presence[j_offset + k];
+                if closed == 0.0 {
+                    continue;
+                }
+
+                // Distribution of the positive-edge count over three independent edges
+                let mut dist = [1.0, 0.0, 0.0, 0.0];
+                for p in [
+                    positive[i_offset + j],
+                    positive[i_offset + k],
+                    positive[j_offset + k],
+                ] {
+                    for c in (0..4).rev() {
+                        let up = if c > 0 { dist[c - 1] * p } else { 0.0 };
+                        dist[c] = dist[c] * (1.0 - p) + up;
+                    }
+                }
+
+                expected.zero_positive += closed * dist[0];
+                expected.one_positive += closed * dist[1];
+                expected.two_positive += closed * dist[2];
+                expected.three_positive += closed * dist[3];
+            }
+        }
+    }
+
+    expected
+}
+
+/// Apply `policy` to non-finite off-diagonal cells of a flat `n * n` matrix
+///
+/// Returns the number of cells replaced with `absent`, the weight that means
+/// no edge under the missing-edge policy.
+fn resolve_non_finite(
+    adj: &mut [f64],
+    n: usize,
+    policy: NonFinitePolicy,
+    absent: f64,
+) -> Result<usize, TriadError> {
+    let mut replaced = 0;
+    for (idx, value) in adj.iter_mut().enumerate() {
+        let (row, col) = (idx / n, idx % n);
+        if row == col || value.is_finite() {
+            continue;
+        }
+        match policy {
+            NonFinitePolicy::Error => {
+                return Err(TriadError::NonFiniteValue {
+                    row,
+                    col,
+                    value: *value,
+                })
+            }
+            NonFinitePolicy::Zero => {
+                *value = absent;
+                replaced += 1;
+            }
+        }
+    }
+    if replaced > 0 {
+        warn!(
+            "Replaced {} non-finite matrix values with no edge",
+            replaced
+        );
+    }
+    Ok(replaced)
+}
+
+/// Keep only sign pairs that agree in both directions of a flat `n * n` sign
+/// matrix, clearing the rest
+fn require_reciprocity(signs: &mut [i8], n: usize) {
+    for i in 0..n {
+        for j in (i + 1)..n {
+            let (forward, backward) = (signs[i * n + j], signs[j * n + i]);
+            let agreed = if forward == backward { forward } else { 0 };
+            signs[i * n + j] = agreed;
+            signs[j * n + i] = agreed;
+        }
+    }
+}
+
+/// What [`TriadCounterPlugin::read_binary`] decoded from the mapped file
+#[cfg(feature = "mmap")]
+enum BinaryLoad {
+    /// The full weight matrix, for options that rewrite it
+    Weights(Vec<f64>),
+    /// Only the signs, with the weights left in the file
+    Signs(Vec<i8>),
+}
+
+/// Flat `n * n` matrix with no edges under `missing` and a zero diagonal
+fn absent_matrix(n: usize, missing: MissingEdgePolicy) -> Vec<f64> {
+    try_absent_matrix(n, missing).expect("matrix size overflows")
+}
+
+/// [`absent_matrix`] for an untrusted `n`: `None` if `n * n` overflows or
+/// the allocation fails, rather than aborting
+fn try_absent_matrix(n: usize, missing: MissingEdgePolicy) -> Option<Vec<f64>> {
+    let len = n.checked_mul(n)?;
+    let mut adj = Vec::new();
+    adj.try_reserve_exact(len).ok()?;
+    adj.resize(len, missing.absent_value());
+    for i in 0..n {
+        adj[i * n + i] = 0.0;
+    }
+    Some(adj)
+}
+
+/// Small seeded generator (SplitMix64) for reproducible random networks
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `[0, 1)` from the top 53 bits
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// CSV records read before each parallel parse step of `input()`
+const PARSE_BATCH_ROWS: usize = 256;
+
+/// Shape of a CSV matrix read by `input()` and its variants
+#[derive(Debug, Clone, Copy)]
+struct CsvLayout {
+    /// The first row and column hold node labels
+    labelled: bool,
+    /// Keep at most this many nodes
+    max_nodes: usize,
+}
+
+impl CsvLayout {
+    /// A complete labelled matrix, as read by `input()`
+    const FULL: CsvLayout = CsvLayout {
+        labelled: true,
+        max_nodes: usize::MAX,
+    };
+
+    /// Leading label columns in each row
+    fn label_columns(self) -> usize {
+        self.labelled as usize
+    }
+}
+
+/// Parse the node columns of one CSV matrix record into `row`, skipping
+/// `label_columns` leading fields
+///
+/// Unparseable cells become 0.0, or with `strict` stop at the first one and
+/// report its column and text.
+fn parse_matrix_row(
+    record: &csv::StringRecord,
+    label_columns: usize,
+    row: &mut [f64],
+    strict: bool,
+) -> Result<(), (usize, String)> {
+    for (col_idx, (field, cell)) in record
+        .iter()
+        .skip(label_columns)
+        .zip(row.iter_mut())
+        .enumerate()
+    {
+        *cell = match field.trim().parse() {
+            Ok(value) => value,
+            Err(_) if strict => return Err((col_idx, field.to_string())),
+            Err(_) => 0.0,
+        };
+    }
+    Ok(())
+}
+
+/// Loading and counting options carried by a plugin
+#[derive(Debug, Clone)]
+struct Config {
+    /// Weights with `|w| <= threshold` are treated as no edge
+    threshold: f64,
+    /// Minimum node count for the parallel counting strategy
+    parallel_threshold: usize,
+    /// CSV field delimiter
+    delimiter: u8,
+    /// Error on unparseable cells instead of treating them as 0
+    strict: bool,
+    /// Handling of NaN and infinite cells
+    non_finite: NonFinitePolicy,
+    /// Which weights mean "no edge"
+    missing: MissingEdgePolicy,
+    /// Keep a pair's edge only when both directions have the same sign
+    reciprocity_required: bool,
+    /// Handling of asymmetric input, checked when loading
+    symmetry_policy: Option<SymmetryPolicy>,
+    /// Rule applied after loading to make the matrix symmetric
+    symmetrization: Option<Symmetrization>,
+    /// Keep loaded diagonal values in `adj` instead of zeroing them
+    keep_diagonal: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            threshold: 0.0,
+            parallel_threshold: DEFAULT_PARALLEL_THRESHOLD,
+            delimiter: b',',
+            strict: false,
+            non_finite: NonFinitePolicy::Error,
+            missing: MissingEdgePolicy::Zero,
+            reciprocity_required: false,
+            symmetry_policy: None,
+            symmetrization: None,
+            keep_diagonal: false,
+        }
+    }
+}
+
+/// TriadCounter plugin for PluMA
+pub struct TriadCounterPlugin {
+    /// Adjacency matrix (stored as flat vector for cache efficiency)
+    adj: Vec<f64>,
+    /// Pre-computed sign matrix: 1 = positive, -1 = negative, 0 = zero
+    signs: Vec<i8>,
+    /// Number of nodes
+    n: usize,
+    /// Node labels
+    labels: Vec<String>,
+    /// Label to index lookup, kept in sync with `labels`
+    label_index: HashMap<String, usize>,
+    /// Computed triad counts
+    counts: TriadCounts,
+    /// Whether `counts` matches the current signs, i.e. `run()` has counted
+    /// them and only incremental updates happened since
+    counts_current: bool,
+    /// Weights as loaded, saved by the first [`symmetrize`](Self::symmetrize)
+    /// call so later calls start from them; dropped whenever `adj` is
+    /// replaced or edited
+    original_adj: Option<Vec<f64>>,
+    /// Loading and counting options
+    config: Config,
+}
+
+impl TriadCounterPlugin {
+    /// Create a new empty plugin
+    pub fn new() -> Self {
+        Self {
+            adj: Vec::new(),
+            signs: Vec::new(),
+            n: 0,
+            labels: Vec::new(),
+            label_index: HashMap::new(),
+            counts: TriadCounts::default(),
+            counts_current: false,
+            original_adj: None,
+            config: Config::default(),
+        }
+    }
+
+    /// Empty the plugin for reuse while keeping its allocations
+    ///
+    /// Clears the matrix, signs, labels, and counts with `Vec::clear()`, so the
+    /// next `input()` of a similar-sized network can reuse the capacity instead
+    /// of reallocating. Configuration (thresholds, delimiter, policies, and so
+    /// on) is preserved.
+    pub fn clear(&mut self) {
+        self.adj.clear();
+        self.original_adj = None;
+        self.signs.clear();
+        self.n = 0;
+        self.labels.clear();
+        self.label_index.clear();
+        self.reset_counts();
+    }
+
+    /// Zero the stored counts after the signs changed, until the next `run()`
+    fn reset_counts(&mut self) {
+        self.counts = TriadCounts::default();
+        self.counts_current = false;
+    }
+
+    /// Replace the node labels and rebuild the name lookup
+    ///
+    /// If a name repeats, lookups resolve to its first occurrence.
+    fn set_labels(&mut self, labels: Vec<String>) {
+        self.label_index = HashMap::with_capacity(labels.len());
+        for (idx, label) in labels.iter().enumerate() {
+            self.label_index.entry(label.clone()).or_insert(idx);
+        }
+        self.labels = labels;
+    }
+
+    /// Convert float to sign: 1 = positive, -1 = negative, 0 = zero
+    #[inline(always)]
+    fn to_sign(v: f64) -> i8 {
+        if v > 0.0 {
+            1
+        } else if v < 0.0 {
+            -1
+        } else {
+            0
+        }
+    }
+
+    /// Convert float to sign, treating `|v| <= eps` as no edge
+    #[inline(always)]
+    fn to_sign_with_threshold(v: f64, eps: f64) -> i8 {
+        if v > eps {
+            1
+        } else if v < -eps {
+            -1
+        } else {
+            0
+        }
+    }
+
+    /// Convert float to sign under a missing-edge policy
+    #[inline(always)]
+    fn to_sign_with_policy(v: f64, eps: f64, missing: MissingEdgePolicy) -> i8 {
+        match missing {
+            MissingEdgePolicy::Zero => Self::to_sign_with_threshold(v, eps),
+            MissingEdgePolicy::Sentinel(sentinel) if v == sentinel => 0,
+            MissingEdgePolicy::Sentinel(_) => {
+                if v > eps {
+                    1
+                } else {
+                    -1
+                }
+            }
+        }
+    }
+
+    /// Set which weights mean "no edge" (default [`MissingEdgePolicy::Zero`])
+    ///
+    /// If a matrix is already loaded its signs are recomputed and the stored
+    /// counts reset; after [`free_adjacency`](Self::free_adjacency) the
+    /// policy only applies from the next load.
+    pub fn set_missing_edge_policy(&mut self, policy: MissingEdgePolicy) {
+        self.config.missing = policy;
+        self.recompute_signs("set_missing_edge_policy");
+    }
+
+    /// Read the matrix as directed and keep only reciprocated edges
+    ///
+    /// When enabled, a pair has an edge of sign `s` only if both `adj[i][j]`
+    /// and `adj[j][i]` have sign `s`; conflicting or one-directional pairs are
+    /// treated as no edge. Off by default, in which case the upper triangle
+    /// decides. If a matrix is already loaded its signs are recomputed and the
+    /// stored counts reset; after [`free_adjacency`](Self::free_adjacency)
+    /// the option only applies from the next load.
+    pub fn set_reciprocity_required(&mut self, required: bool) {
+        self.config.reciprocity_required = required;
+        self.recompute_signs("set_reciprocity_required");
+    }
+
+    /// Treat weights with `|v| <= eps` as no edge (default 0.0)
+    ///
+    /// Values above `eps` are positive and below `-eps` negative. If a matrix
+    /// is already loaded its signs are recomputed and the stored counts reset;
+    /// after [`free_adjacency`](Self::free_adjacency) the threshold only
+    /// applies from the next load.
+    pub fn set_threshold(&mut self, eps: f64) {
+        self.config.threshold = eps;
+        self.recompute_signs("set_threshold");
+    }
+
+    /// Recompute the signs of a loaded matrix after a sign option changed,
+    /// and reset the stored counts
+    ///
+    /// Without weights the current signs are kept and the change waits for
+    /// the next load.
+    fn recompute_signs(&mut self, method: &str) {
+        if self.n == 0 {
+            return;
+        }
+        if self.has_adjacency() {
+            self.compute_signs();
+            self.reset_counts();
+        } else {
+            warn!(
+                "{} applies from the next load; the loaded weights were freed",
+                method
+            );
+        }
+    }
+
+    /// Current sign threshold
+    pub fn threshold(&self) -> f64 {
+        self.config.threshold
+    }
+
+    /// Field delimiter used by the text loaders (default `b','`)
+    ///
+    /// Use `b'\t'` for TSV or `b';'` for European-locale spreadsheet exports.
+    pub fn set_delimiter(&mut self, delim: u8) {
+        self.config.delimiter = delim;
+    }
+
+    /// Current field delimiter
+    pub fn delimiter(&self) -> u8 {
+        self.config.delimiter
+    }
+
+    /// Fail on the first unparseable cell instead of treating it as 0
+    ///
+    /// With strict parsing, `input()` reports cells such as `N/A` or `1,2` as
+    /// [`TriadError::InvalidValue`] with their row and column. Empty cells
+    /// are invalid too. Off by default.
+    pub fn set_strict(&mut self, strict: bool) {
+        self.config.strict = strict;
+    }
+
+    /// Set how NaN and infinite matrix values are handled (default: error)
+    pub fn set_non_finite_policy(&mut self, policy: NonFinitePolicy) {
+        self.config.non_finite = policy;
+    }
+
+    /// Keep diagonal values when loading a matrix (default: zero them)
+    ///
+    /// Applies to [`input`](Self::input), its variants, and
+    /// [`input_binary`](Self::input_binary); the diagonal is then readable
+    /// through [`adjacency`](Self::adjacency). It is never an edge, so counts
+    /// are unaffected, and the non-finite policy does not look at it. The
+    /// `from_*` constructors always zero the diagonal.
+    pub fn set_keep_diagonal(&mut self, keep: bool) {
+        self.config.keep_diagonal = keep;
+    }
+
+    /// Pre-compute sign matrix for fast access
+    fn compute_signs(&mut self) {
+        let eps = self.config.threshold;
+        Self::signs_into(&self.adj, self.n, &self.config, eps, &mut self.signs);
+    }
+
+    /// Fill `signs` from `adj` with threshold `eps` and the other sign options
+    /// in `config`, reusing the vector's capacity
+    fn signs_into(adj: &[f64], n: usize, config: &Config, eps: f64, signs: &mut Vec<i8>) {
+        let missing = config.missing;
+        signs.clear();
+        signs.extend(
+            adj.iter()
+                .map(|&v| Self::to_sign_with_policy(v, eps, missing)),
+        );
+        // The diagonal is never an edge, whatever the policy says about 0.0
+        for i in 0..n {
+            signs[i * n + i] = 0;
+        }
+
+        if config.reciprocity_required {
+            require_reciprocity(signs, n);
+        }
+    }
+
+    /// Load adjacency matrix from CSV file
+    ///
+    /// On failure the plugin is left empty, as after [`clear`](Self::clear).
+    pub fn input<P: AsRef<Path>>(&mut self, path: P) -> Result<(), TriadError> {
+        match open_input(path) {
+            Ok(source) => self.load_matrix(source, CsvLayout::FULL),
+            Err(e) => {
+                self.clear();
+                Err(e)
+            }
+        }
+    }
+
+    /// Load adjacency matrix from CSV text already in memory
+    ///
+    /// Same format, options, and error handling as [`input`](Self::input),
+    /// without touching the filesystem.
+    pub fn input_str(&mut self, contents: &str) -> Result<(), TriadError> {
+        self.load_matrix(contents.as_bytes(), CsvLayout::FULL)
+    }
+
+    /// Load only the first `max_nodes` nodes of a CSV adjacency matrix
+    ///
+    /// Reads the first `max_nodes` header labels and the top-left
+    /// `max_nodes * max_nodes` submatrix, and stops reading the file there,
+    /// so a prototype run on a huge matrix does not pay for the whole file.
+    /// The result depends only on file order, not on any property of the
+    /// nodes, so it is meant for quick experiments rather than analysis.
+    /// Otherwise behaves like [`input`](Self::input); a file with fewer nodes
+    /// loads completely.
+    pub fn input_limited<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        max_nodes: usize,
+    ) -> Result<(), TriadError> {
+        match open_input(path) {
+            Ok(source) => self.load_matrix(
+                source,
+                CsvLayout {
+                    labelled: true,
+                    max_nodes,
+                },
+            ),
+            Err(e) => {
+                self.clear();
+                Err(e)
+            }
+        }
+    }
+
+    /// Load a purely numeric CSV matrix with no label row or column
+    ///
+    /// The first row's width sets the node count, and nodes are named
+    /// `Node0`, `Node1`, and so on. Otherwise behaves like
+    /// [`input`](Self::input), including every loading option.
+    pub fn input_headerless<P: AsRef<Path>>(&mut self, path: P) -> Result<(), TriadError> {
+        match open_input(path) {
+            Ok(source) => self.load_matrix(
+                source,
+                CsvLayout {
+                    labelled: false,
+                    max_nodes: usize::MAX,
+                },
+            ),
+            Err(e) => {
+                self.clear();
+                Err(e)
+            }
+        }
+    }
+
+    /// Shared body of [`input`](Self::input) and its variants
+    fn load_matrix<R: std::io::Read>(
+        &mut self,
+        source: R,
+        layout: CsvLayout,
+    ) -> Result<(), TriadError> {
+        // Parse into the previous matrix's buffer to reuse its allocation
+        let buffer = std::mem::take(&mut self.adj);
+        let (labels, adj) = match self.read_matrix_csv(source, buffer, layout) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                // The old matrix went to the parser, so don't leave it half there
+                self.clear();
+                return Err(e);
+            }
+        };
+        self.n = labels.len();
+        self.set_labels(labels);
+        self.adj = adj;
+        self.finish_matrix_load()?;
+        info!("Loaded adjacency matrix with {} nodes", self.n);
+
+        Ok(())
+    }
+
+    /// Parse a CSV matrix into labels and a flat matrix, applying the
+    /// delimiter, strictness, non-finite, and diagonal options
+    ///
+    /// The matrix is written into `buffer`, reusing its capacity. Only the
+    /// first `layout.max_nodes` nodes and the matching rows and columns are
+    /// read; rows past them are left unread.
+    fn read_matrix_csv<R: std::io::Read>(
+        &self,
+        source: R,
+        mut buffer: Vec<f64>,
+        layout: CsvLayout,
+    ) -> Result<(Vec<String>, Vec<f64>), TriadError> {
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(layout.labelled)
+            .flexible(true)
+            .comment(Some(COMMENT_PREFIX))
+            .delimiter(self.config.delimiter)
+            .from_reader(source);
+        let mut batch = vec![csv::StringRecord::new(); PARSE_BATCH_ROWS];
+        // Data rows already in `batch` before the first parse step
+        let mut pending = 0;
+
+        // Labels come from the header, or are generated from the width of
+        // the first row
+        let (labels, columns): (Vec<String>, usize) = if layout.labelled {
+            let headers = reader.headers()?;
+            let labels = headers
+                .iter()
+                .skip(1)
+                .take(layout.max_nodes)
+                .map(|s| s.to_string())
+                .collect();
+            (labels, headers.len().saturating_sub(1))
+        } else {
+            if reader.read_record(&mut batch[0])? {
+                pending = 1;
+            }
+            let columns = batch[0].len();
+            let labels = (0..columns.min(layout.max_nodes))
+                .map(|i| format!("Node{}", i))
+                .collect();
+            (labels, columns)
+        };
+        let n = labels.len();
+        if n == 0 {
+            return Err(TriadError::EmptyMatrix);
+        }
+        let limited = n < columns;
+        let mut seen: HashMap<&str, usize> = HashMap::with_capacity(n);
+        for (second, name) in labels.iter().enumerate() {
+            if let Some(&first) = seen.get(name.as_str()) {
+                return Err(TriadError::DuplicateLabel {
+                    name: name.clone(),
+                    first,
+                    second,
+                });
+            }
+            seen.insert(name, second);
+        }
+
+        // Pre-allocate adjacency matrix
+        buffer.clear();
+        buffer.resize(n * n, 0.0);
+        let mut adj = buffer;
+
+        // Read rows in batches, parsing each batch's rows in parallel
+        let strict = self.config.strict;
+        let label_columns = layout.label_columns();
+        let mut rows = 0;
+        loop {
+            let wanted = if limited {
+                batch.len().min(n - rows)
+            } else {
+                batch.len()
+            };
+            let mut filled = std::mem::take(&mut pending);
+            while filled < wanted && reader.read_record(&mut batch[filled])? {
+                filled += 1;
+            }
+            if filled == 0 {
+                break;
+            }
+
+            let first_row = rows;
+            rows += filled;
+            // Extra rows are only counted, for the squareness error below
+            if first_row >= n {
+                continue;
+            }
+            let usable = filled.min(n - first_row);
+
+            let rows_in_batch = &mut adj[first_row * n..(first_row + usable) * n];
+            #[cfg(feature = "parallel")]
+            let rows_in_batch = rows_in_batch.par_chunks_mut(n);
+            #[cfg(not(feature = "parallel"))]
+            let rows_in_batch = rows_in_batch.chunks_mut(n);
+            let failure = rows_in_batch
+                .zip(&batch[..usable])
+                .enumerate()
+                .filter_map(|(offset, (row, record))| {
+                    parse_matrix_row(record, label_columns, row, strict)
+                        .err()
+                        .map(|(col, value)| (first_row + offset, col, value))
+                })
+                .min_by_key(|&(row, col, _)| (row, col));
+            if let Some((row, col, value)) = failure {
+                return Err(TriadError::InvalidValue { row, col, value });
+            }
+        }
+
+        if rows != n {
+            return Err(TriadError::NonSquareMatrix { rows, cols: n });
+        }
+        resolve_non_finite(
+            &mut adj,
+            n,
+            self.config.non_finite,
+            self.config.missing.absent_value(),
+        )?;
+
+        if !self.config.keep_diagonal {
+            for i in 0..n {
+                adj[i * n + i] = 0.0;
+            }
+        }
+
+        Ok((labels, adj))
+    }
+
+    /// Apply the configured symmetry handling to a freshly loaded matrix and
+    /// compute its signs
+    ///
+    /// On failure the plugin is cleared, so it never pairs the new matrix
+    /// with the previous signs.
+    fn finish_matrix_load(&mut self) -> Result<(), TriadError> {
+        self.original_adj = None;
+        if let Some(policy) = self.config.symmetry_policy {
+            if let Err(e) = self.apply_symmetry_policy(policy) {
+                self.clear();
+                return Err(e);
+            }
+        }
+
+        // Pre-compute signs (symmetrizing recomputes them itself)
+        match self.config.symmetrization {
+            Some(rule) => self.symmetrize_in_place(rule),
+            None => self.compute_signs(),
+        }
+        self.reset_counts();
+
+        Ok(())
+    }
+
+    /// Load positive and negative edges from two labelled CSV matrices
+    ///
+    /// Any cell in `positive` other than 0 (or the
+    /// [missing-edge](Self::set_missing_edge_policy) sentinel) becomes +1, and
+    /// likewise any such cell in `negative` becomes -1; magnitudes are
+    /// ignored. Labels are the union of both headers, in order of first
+    /// appearance (positive file first), and pairs absent from both files have
+    /// no edge. A cell marked in both files
+    /// fails with [`TriadError::ConflictingEdge`]. The symmetry policy and
+    /// symmetrization then apply as for [`input`](Self::input), and on
+    /// failure the plugin is left empty.
+    pub fn input_layered<P: AsRef<Path>>(
+        &mut self,
+        positive: P,
+        negative: P,
+    ) -> Result<(), TriadError> {
+        let (labels, adj) = match self.read_layered(positive, negative) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                self.clear();
+                return Err(e);
+            }
+        };
+
+        self.n = labels.len();
+        self.set_labels(labels);
+        self.adj = adj;
+        self.finish_matrix_load()?;
+        info!("Loaded layered network with {} nodes", self.n);
+
+        Ok(())
+    }
+
+    /// Read both layer files and merge them into labels and a flat matrix of
+    /// +1, -1, and the missing-edge value
+    fn read_layered<P: AsRef<Path>>(
+        &self,
+        positive: P,
+        negative: P,
+    ) -> Result<(Vec<String>, Vec<f64>), TriadError> {
+        let layers = [
+            (
+                self.read_matrix_csv(open_input(positive)?, Vec::new(), CsvLayout::FULL)?,
+                1,
+            ),
+            (
+                self.read_matrix_csv(open_input(negative)?, Vec::new(), CsvLayout::FULL)?,
+                -1,
+            ),
+        ];
+
+        let mut labels: Vec<String> = Vec::new();
+        let mut index: HashMap<String, usize> = HashMap::new();
+        for ((layer_labels, _), _) in &layers {
+            for label in layer_labels {
+                if !index.contains_key(label) {
+                    index.insert(label.clone(), labels.len());
+                    labels.push(label.clone());
+                }
+            }
+        }
+
+        let n = labels.len();
+        let absent = self.config.missing.absent_value();
+        let mut marks = vec![0i8; n * n];
+        for ((layer_labels, layer_adj), sign) in &layers {
+            let m = layer_labels.len();
+            let map: Vec<usize> = layer_labels.iter().map(|l| index[l]).collect();
+            for (row, &u) in map.iter().enumerate() {
+                for (col, &v) in map.iter().enumerate() {
+                    let value = layer_adj[row * m + col];
+                    if value == 0.0 || value == absent {
+                        continue;
+                    }
+                    let mark = &mut marks[u * n + v];
+                    if *mark != 0 && *mark != *sign {
+                        return Err(TriadError::ConflictingEdge {
+                            row: labels[u].clone(),
+                            col: labels[v].clone(),
+                        });
+                    }
+                    *mark = *sign;
+                }
+            }
+        }
+        let mut adj = absent_matrix(n, self.config.missing);
+        for (cell, &mark) in adj.iter_mut().zip(&marks) {
+            if mark != 0 {
+                *cell = f64::from(mark);
+            }
+        }
+
+        Ok((labels, adj))
+    }
+
+    /// Load a raw little-endian `f64` matrix of `n * n` values, row-major
+    ///
+    /// The file is memory-mapped and its signs are computed straight from the
+    /// mapped bytes, skipping text parsing entirely. Labels come from a
+    /// sidecar file next to it with the extension `.labels` (e.g. `net.bin`
+    /// and `net.labels`), one label per line; without one, nodes are named
+    /// `Node0`, `Node1`, and so on. The non-finite policy, missing-edge
+    /// policy, threshold, and reciprocity option apply as for
+    /// [`input`](Self::input), and `RequireSymmetric` is checked against the
+    /// mapped values.
+    ///
+    /// Only the one-byte-per-pair signs are kept, so a 20000-node matrix
+    /// takes 400 MB rather than 3.2 GB: the weights are not copied and the
+    /// plugin is left as after [`free_adjacency`](Self::free_adjacency). If a
+    /// rewriting symmetry policy or symmetrization is configured, the
+    /// weights are needed, so the whole matrix is decoded into memory (eight
+    /// bytes per pair) and loaded as by `input()` instead, diagonal option
+    /// included.
+    ///
+    /// Fails with [`TriadError::DimensionMismatch`] if the file does not hold
+    /// exactly `n * n` values or the sidecar does not hold `n` labels. On
+    /// failure the plugin is left empty.
+    #[cfg(feature = "mmap")]
+    pub fn input_binary<P: AsRef<Path>>(&mut self, path: P, n: usize) -> Result<(), TriadError> {
+        let (labels, load) = match self.read_binary(path.as_ref(), n) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                self.clear();
+                return Err(e);
+            }
+        };
+
+        self.n = n;
+        self.set_labels(labels);
+        match load {
+            BinaryLoad::Weights(adj) => {
+                self.adj = adj;
+                self.finish_matrix_load()?;
+                info!("Loaded binary matrix with {} nodes", self.n);
+            }
+            BinaryLoad::Signs(signs) => {
+                self.adj = Vec::new();
+                self.original_adj = None;
+                self.signs = signs;
+                self.reset_counts();
+                info!("Loaded binary matrix signs with {} nodes", self.n);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Map a binary matrix and read its labels, decoding either the weights
+    /// or just the signs as described on [`input_binary`](Self::input_binary)
+    #[cfg(feature = "mmap")]
+    fn read_binary(&self, path: &Path, n: usize) -> Result<(Vec<String>, BinaryLoad), TriadError> {
+        let file = std::fs::File::open(path)?;
+        // SAFETY: the map is only read for the duration of this call; as with
+        // any mmap, the file must not be truncated concurrently.
+        let map = unsafe { memmap2::Mmap::map(&file)? };
+
+        const WIDTH: usize = std::mem::size_of::<f64>();
+        // `n` comes from the caller, so a huge one must not wrap into a match
+        let len = match n.checked_mul(n) {
+            Some(len) if len.checked_mul(WIDTH) == Some(map.len()) => len,
+            _ => {
+                return Err(TriadError::DimensionMismatch {
+                    expected: n.saturating_mul(n),
+                    found: map.len() / WIDTH,
+                })
+            }
+        };
+        if n == 0 {
+            return Err(TriadError::EmptyMatrix);
+        }
+
+        let labels = match std::fs::read_to_string(path.with_extension("labels")) {
+            Ok(text) => {
+                let labels: Vec<String> = text.lines().map(|l| l.trim().to_string()).collect();
+                if labels.len() != n {
+                    return Err(TriadError::DimensionMismatch {
+                        expected: n,
+                        found: labels.len(),
+                    });
+                }
+                labels
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                (0..n).map(|i| format!("Node{}", i)).collect()
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        let value = |idx: usize| {
+            let bytes = &map[idx * WIDTH..(idx + 1) * WIDTH];
+            f64::from_le_bytes(bytes.try_into().expect("slice is 8 bytes"))
+        };
+        let absent = self.config.missing.absent_value();
+
+        let rewrites = self.config.symmetrization.is_some()
+            || self
+                .config
+                .symmetry_policy
+                .and_then(SymmetryPolicy::rule)
+                .is_some();
+        if rewrites {
+            let mut adj: Vec<f64> = (0..len).map(value).collect();
+            drop(map);
+            resolve_non_finite(&mut adj, n, self.config.non_finite, absent)?;
+            if !self.config.keep_diagonal {
+                for i in 0..n {
+                    adj[i * n + i] = 0.0;
+                }
+            }
+            return Ok((labels, BinaryLoad::Weights(adj)));
+        }
+
+        let (eps, missing) = (self.config.threshold, self.config.missing);
+        let mut signs: Vec<i8> = Vec::with_capacity(len);
+        let mut replaced = 0;
+        for idx in 0..len {
+            let (row, col) = (idx / n, idx % n);
+            let mut v = value(idx);
+            if row == col {
+                signs.push(0);
+                continue;
+            }
+            if !v.is_finite() {
+                match self.config.non_finite {
+                    NonFinitePolicy::Error => {
+                        return Err(TriadError::NonFiniteValue { row, col, value: v })
+                    }
+                    NonFinitePolicy::Zero => {
+                        v = absent;
+                        replaced += 1;
+                    }
+                }
+            }
+            signs.push(Self::to_sign_with_policy(v, eps, missing));
+        }
+        if replaced > 0 {
+            warn!(
+                "Replaced {} non-finite matrix values with no edge",
+                replaced
+            );
+        }
+
+        if self.config.symmetry_policy == Some(SymmetryPolicy::RequireSymmetric) {
+            // Any non-finite value was replaced with `absent` above
+            let resolved = |idx: usize| {
+                let v = value(idx);
+                if v.is_finite() {
+                    v
+                } else {
+                    absent
+                }
+            };
+            for i in 0..n {
+                for j in (i + 1)..n {
+                    let (upper, lower) = (resolved(i * n + j), resolved(j * n + i));
+                    if upper != lower {
+                        return Err(TriadError::AsymmetricMatrix {
+                            row: i,
+                            col: j,
+                            upper,
+                            lower,
+                        });
+                    }
+                }
+            }
+        }
+        drop(map);
+        if self.config.reciprocity_required {
+            require_reciprocity(&mut signs, n);
+        }
+
+        Ok((labels, BinaryLoad::Signs(signs)))
+    }
+
+    /// Load an undirected signed edge list with `source,target,sign` rows
+    ///
+    /// Labels are the union of sources and targets in order of first
+    /// appearance. Every row sets both directions, so a missing reverse row is
+    /// implied rather than treated as absent; if a pair appears more than once
+    /// (in either direction) the last row wins. An optional header row is
+    /// detected by a non-numeric sign column. Self-loops are ignored. The
+    /// non-finite, symmetry, and symmetrization options apply as for
+    /// [`input`](Self::input), and on failure the plugin is left empty.
+    pub fn input_edgelist<P: AsRef<Path>>(&mut self, path: P) -> Result<(), TriadError> {
+        let (labels, adj) = match open_input(path).and_then(|source| self.read_edgelist(source)) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                self.clear();
+                return Err(e);
+            }
+        };
+
+        self.n = labels.len();
+        self.set_labels(labels);
+        self.adj = adj;
+        self.finish_matrix_load()?;
+        info!("Loaded edge list with {} nodes", self.n);
+
+        Ok(())
+    }
+
+    /// Parse an edge list into labels and a flat symmetric matrix, applying
+    /// the delimiter, strictness, and non-finite options
+    fn read_edgelist<R: std::io::Read>(
+        &self,
+        source: R,
+    ) -> Result<(Vec<String>, Vec<f64>), TriadError> {
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .comment(Some(COMMENT_PREFIX))
+            .delimiter(self.config.delimiter)
+            .from_reader(source);
+
+        let mut labels: Vec<String> = Vec::new();
+        let mut index: HashMap<String, usize> = HashMap::new();
+        let mut edges: Vec<(usize, usize, f64)> = Vec::new();
+
+        for (row_idx, result) in reader.records().enumerate() {
+            let record = result?;
+            if record.len() < 3 {
+                return Err(TriadError::Malformed {
+                    line: row_idx + 1,
+                    message: format!("expected 3 columns, found {}", record.len()),
+                });
+            }
+
+            let value: f64 = match record[2].trim().parse() {
+                Ok(value) => value,
+                Err(_) if row_idx == 0 => continue, // header row
+                Err(_) if self.config.strict => {
+                    return Err(TriadError::InvalidValue {
+                        row: row_idx,
+                        col: 2,
+                        value: record[2].to_string(),
+                    })
+                }
+                Err(_) => self.config.missing.absent_value(),
+            };
+
+            let mut node = |name: &str| {
+                let name = name.trim();
+                *index.entry(name.to_string()).or_insert_with(|| {
+                    labels.push(name.to_string());
+                    labels.len() - 1
+                })
+            };
+            let (u, v) = (node(&record[0]), node(&record[1]));
+            edges.push((u, v, value));
+        }
+
+        let n = labels.len();
+        let mut adj = absent_matrix(n, self.config.missing);
+        for (u, v, value) in edges {
+            if u != v {
+                adj[u * n + v] = value;
+                adj[v * n + u] = value;
+            }
+        }
+        resolve_non_finite(
+            &mut adj,
+            n,
+            self.config.non_finite,
+            self.config.missing.absent_value(),
+        )?;
+
+        Ok((labels, adj))
+    }
+
+    /// Load a signed network from a DIMACS-style edge file
+    ///
+    /// Expects a `p edge <n> <m>` problem line followed by `m` lines of the form
+    /// `e <u> <v> <s>` with 1-indexed vertices and signed weight `s`; `c` lines
+    /// are comments. Each edge is stored symmetrically and nodes are labelled
+    /// `Node0..Node{n-1}`. Fails if the number of `e` lines differs from `m`.
+    /// NaN and infinite weights follow the
+    /// [non-finite policy](Self::set_non_finite_policy).
+    ///
+    /// On failure the plugin is left empty, as after [`clear`](Self::clear).
+    pub fn input_dimacs<P: AsRef<Path>>(&mut self, path: P) -> Result<(), TriadError> {
+        let (n, m, adj) = match open_input(path).and_then(|source| self.read_dimacs(source)) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                self.clear();
+                return Err(e);
+            }
+        };
+
+        self.n = n;
+        self.set_labels((0..n).map(|i| format!("Node{}", i)).collect());
+        self.adj = adj;
+        self.finish_matrix_load()?;
+        info!("Loaded DIMACS network with {} nodes and {} edges", n, m);
+
+        Ok(())
+    }
+
+    /// Parse a DIMACS edge file into its node count, edge count, and flat
+    /// matrix with a zero diagonal, applying the non-finite policy
+    fn read_dimacs<R: std::io::Read>(
+        &self,
+        source: R,
+    ) -> Result<(usize, usize, Vec<f64>), TriadError> {
+        use std::io::BufRead;
+
+        let reader = std::io::BufReader::new(source);
+        // Node count, edge count, and line of the problem line
+        let mut declared: Option<(usize, usize, usize)> = None;
+        let mut edges: Vec<(usize, usize, f64)> = Vec::new();
+
+        for (line_idx, line) in reader.lines().enumerate() {
+            let line = line?;
+            let line_no = line_idx + 1;
+            let malformed = |message: String| TriadError::Malformed {
+                line: line_no,
+                message,
+            };
+            let mut fields = line.split_whitespace();
+
+            match fields.next() {
+                None | Some("c") => continue,
+                Some("p") => {
+                    if declared.is_some() {
+                        return Err(malformed("duplicate problem line".to_string()));
+                    }
+                    let parsed = match (fields.next(), fields.next(), fields.next()) {
+                        (Some("edge"), Some(n), Some(m)) => {
+                            n.parse::<usize>().ok().zip(m.parse().ok())
+                        }
+                        _ => None,
+                    };
+                    let (n, m) =
+                        parsed.ok_or_else(|| malformed("expected 'p edge <n> <m>'".to_string()))?;
+                    declared = Some((n, m, line_no));
+                }
+                Some("e") => {
+                    let (n, _, _) = declared
+                        .ok_or_else(|| malformed("edge before problem line".to_string()))?;
+                    let parsed = match (fields.next(), fields.next(), fields.next()) {
+                        (Some(u), Some(v), Some(s)) => {
+                            match (u.parse::<usize>(), v.parse::<usize>(), s.parse::<f64>()) {
+                                (Ok(u), Ok(v), Ok(value)) => Some((u, v, value)),
+                                _ => None,
+                            }
+                        }
+                        _ => None,
+                    };
+                    let (u, v, value) =
+                        parsed.ok_or_else(|| malformed("expected 'e <u> <v> <s>'".to_string()))?;
+                    if u == 0 || v == 0 || u > n || v > n {
+                        return Err(malformed(format!("vertex out of range 1..={}", n)));
+                    }
+                    edges.push((u - 1, v - 1, value));
+                }
+                Some(other) => {
+                    return Err(malformed(format!("unknown line type '{}'", other)));
+                }
+            }
+        }
+
+        let (n, m, problem_line) = declared.ok_or_else(|| TriadError::Malformed {
+            line: 0,
+            message: "missing problem line".to_string(),
+        })?;
+        if edges.len() != m {
+            return Err(TriadError::EdgeCountMismatch {
+                declared: m,
+                found: edges.len(),
+            });
+        }
+
+        // Only allocate once the file is known to be complete; the node count
+        // is untrusted, so an impossible matrix is an error, not an abort
+        let mut adj =
+            try_absent_matrix(n, self.config.missing).ok_or_else(|| TriadError::Malformed {
+                line: problem_line,
+                message: format!("cannot allocate a matrix for {} nodes", n),
+            })?;
+        for (u, v, value) in edges {
+            adj[u * n + v] = value;
+            adj[v * n + u] = value;
+        }
+        // A self-loop is not an edge
+        for i in 0..n {
+            adj[i * n + i] = 0.0;
+        }
+        resolve_non_finite(
+            &mut adj,
+            n,
+            self.config.non_finite,
+            self.config.missing.absent_value(),
+        )?;
+
+        Ok((n, m, adj))
+    }
+
+    /// Set how asymmetric matrices are handled
+    ///
+    /// The policy is applied by every subsequent `input()`. If a matrix is
+    /// already loaded it is applied immediately, so `RequireSymmetric` may fail
+    /// here, as may any policy with [`TriadError::WeightsFreed`] after
+    /// [`free_adjacency`](Self::free_adjacency); without a policy the counters
+    /// implicitly read the upper triangle.
+    pub fn set_symmetry_policy(&mut self, policy: SymmetryPolicy) -> Result<(), TriadError> {
+        self.config.symmetry_policy = Some(policy);
+        if self.n > 0 {
+            self.apply_symmetry_policy(policy)?;
+        }
+        Ok(())
+    }
+
+    /// Check or rewrite the loaded matrix according to `policy`
+    fn apply_symmetry_policy(&mut self, policy: SymmetryPolicy) -> Result<(), TriadError> {
+        if let Some(rule) = policy.rule() {
+            if !self.has_adjacency() {
+                return Err(TriadError::WeightsFreed);
+            }
+            self.symmetrize_in_place(rule);
+            return Ok(());
+        }
+
+        self.check_symmetric()
+    }
+
+    /// Fail with [`TriadError::AsymmetricMatrix`] at the first pair whose
+    /// stored weights differ between directions
+    fn check_symmetric(&self) -> Result<(), TriadError> {
+        if !self.has_adjacency() {
+            return Err(TriadError::WeightsFreed);
+        }
+        match self.asymmetric_pairs().next() {
+            Some((row, col, upper, lower)) => Err(TriadError::AsymmetricMatrix {
+                row,
+                col,
+                upper,
+                lower,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Pairs `i < j` with `adj[i][j] != adj[j][i]`, as `(i, j, upper, lower)`
+    /// in row-major order
+    fn asymmetric_pairs(&self) -> impl Iterator<Item = (usize, usize, f64, f64)> + '_ {
+        let n = self.n;
+        (0..n)
+            .flat_map(move |i| ((i + 1)..n).map(move |j| (i, j)))
+            .map(move |(i, j)| (i, j, self.adj[i * n + j], self.adj[j * n + i]))
+            .filter(|&(_, _, upper, lower)| upper != lower)
+    }
+
+    /// Every pair whose two directions hold different weights
+    ///
+    /// Each unordered pair is listed once as `(i, j, adj[i][j], adj[j][i])`
+    /// with `i < j`, in row-major order; empty for a symmetric matrix. A
+    /// data-quality check, e.g. for exports that dropped one triangle, that
+    /// works whatever the [`SymmetryPolicy`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the weights were freed with
+    /// [`free_adjacency`](Self::free_adjacency).
+    pub fn symmetry_report(&self) -> Vec<(usize, usize, f64, f64)> {
+        self.require_weights("symmetry_report");
+        self.asymmetric_pairs().collect()
+    }
+
+    /// Whether `adj[i][j] == adj[j][i]` for every pair
+    ///
+    /// After [`free_adjacency`](Self::free_adjacency) only the signs remain,
+    /// so they are compared instead.
+    pub fn is_symmetric(&self) -> bool {
+        if !self.has_adjacency() {
+            let n = self.n;
+            return (0..n)
+                .all(|i| ((i + 1)..n).all(|j| self.signs[i * n + j] == self.signs[j * n + i]));
+        }
+        self.check_symmetric().is_ok()
+    }
+
+    /// Most compact layout that represents this network without loss
+    ///
+    /// Symmetric networks can be counted from an [`UpperTriangularSigns`]
+    /// with half the memory, or from a [`SparseSigns`] below
+    /// [`SPARSE_DENSITY`]; asymmetric ones need [`Storage::Full`].
+    pub fn recommended_storage(&self) -> Storage {
+        if !self.is_symmetric() {
+            Storage::Full
+        } else if self.network_summary().density < SPARSE_DENSITY {
+            Storage::Sparse
+        } else {
+            Storage::UpperTriangular
+        }
+    }
+
+    /// Make the loaded matrix symmetric according to `rule`
+    ///
+    /// Each call starts from the weights as loaded, so different rules can be
+    /// tried in turn without reloading: the first call saves a copy of the
+    /// matrix (another `n * n` weights) and later calls symmetrize from it.
+    /// Editing the weights with [`update_edge`](Self::update_edge) or
+    /// [`remove_nodes`](Self::remove_nodes), freeing them, or loading another
+    /// network drops the copy, and the next call starts from the current
+    /// weights. A read-time [`SymmetryPolicy`] or symmetrization is already
+    /// part of the loaded weights.
+    ///
+    /// Signs are recomputed and the stored counts are reset, so call `run()`
+    /// again afterwards.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the weights were freed with
+    /// [`free_adjacency`](Self::free_adjacency).
+    pub fn symmetrize(&mut self, rule: Symmetrization) {
+        self.require_weights("symmetrize");
+        let original = self.original_adj.get_or_insert_with(|| self.adj.clone());
+        let n = self.n;
+        for i in 0..n {
+            for j in (i + 1)..n {
+                let value = rule.combine(original[i * n + j], original[j * n + i]);
+                self.adj[i * n + j] = value;
+                self.adj[j * n + i] = value;
+            }
+        }
+        self.compute_signs();
+        self.reset_counts();
+    }
+
+    /// Rewrite the current weights to be symmetric according to `rule`,
+    /// without keeping the originals
+    fn symmetrize_in_place(&mut self, rule: Symmetrization) {
+        self.require_weights("symmetrize");
+        self.original_adj = None;
+        let n = self.n;
+        for i in 0..n {
+            for j in (i + 1)..n {
+                let value = rule.combine(self.adj[i * n + j], self.adj[j * n + i]);
+                self.adj[i * n + j] = value;
+                self.adj[j * n + i] = value;
+            }
+        }
+        self.compute_signs();
+        self.reset_counts();
+    }
+
+    /// Count triads - automatically chooses best strategy
+    ///
+    /// Returns the new counts, which also remain available via `counts()`.
+    pub fn run(&mut self) -> &TriadCounts {
+        if self.signs.is_empty() {
+            self.compute_signs();
+        }
+        if self.is_sign_skewed() {
+            warn!(
+                "{:.1}% of edges are positive; balance metrics are nearly determined by the sign distribution",
+                self.positive_fraction() * 100.0
+            );
+        }
+        self.counts = self.count_triads_optimized();
+        self.counts_current = true;
+        &self.counts
+    }
+
+    /// Like [`run`](Self::run), but on a dedicated pool of `threads` threads
+    ///
+    /// Limits the parallel strategy to an allocation, e.g. on a shared
+    /// cluster. An explicit count takes precedence over `RAYON_NUM_THREADS`,
+    /// which otherwise sizes rayon's global pool; `threads == 0` uses that
+    /// default. The pool lives only for this call. Fails with
+    /// [`TriadError::Io`] if the threads cannot be spawned. Without the
+    /// `parallel` feature counting is single-threaded anyway and `threads` is
+    /// ignored.
+    pub fn run_in_pool(&mut self, threads: usize) -> Result<&TriadCounts, TriadError> {
+        #[cfg(feature = "parallel")]
+        {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .map_err(std::io::Error::other)?;
+            Ok(pool.install(|| self.run()))
+        }
+        #[cfg(not(feature = "parallel"))]
+        {
+            let _ = threads;
+            Ok(self.run())
+        }
+    }
+
+    /// Count positive, negative, and absent pairs in one upper-triangle pass
+    ///
+    /// Cheap (O(n²)) compared to counting triads, so useful as a sanity check
+    /// on freshly loaded input.
+    pub fn network_summary(&self) -> NetworkSummary {
+        let n = self.n;
+        let (mut positive_edges, mut negative_edges, mut zero_entries) = (0u64, 0u64, 0u64);
+
+        for i in 0..n {
+            for &sign in &self.signs[i * n + i + 1..(i + 1) * n] {
+                match sign {
+                    1 => positive_edges += 1,
+                    -1 => negative_edges += 1,
+                    _ => zero_entries += 1,
+                }
+            }
+        }
+
+        let pairs = positive_edges + negative_edges + zero_entries;
+        let density = if pairs == 0 {
+            0.0
+        } else {
+            (positive_edges + negative_edges) as f64 / pairs as f64
+        };
+
+        NetworkSummary {
+            positive_edges,
+            negative_edges,
+            zero_entries,
+            density,
+        }
+    }
+
+    /// Estimate the cost of counting this network without counting it
+    ///
+    /// One O(n²) pass over the signs, so it can be run before committing to a
+    /// long count on a huge matrix.
+    pub fn estimate_work(&self) -> WorkEstimate {
+        let n = self.n;
+        let summary = self.network_summary();
+
+        let mut inner_checks = 0u64;
+        for i in 0..n {
+            for j in (i + 1)..n {
+                if self.signs[i * n + j] != 0 {
+                    inner_checks += (n - j - 1) as u64;
+                }
+            }
+        }
+
+        let candidate_triads = choose3(n);
+        WorkEstimate {
+            candidate_triads,
+            edges: summary.edges(),
+            skip_rate: if n < 2 { 0.0 } else { 1.0 - summary.density },
+            inner_checks,
+            expected_triads: candidate_triads as f64 * summary.density.powi(3),
+        }
+    }
+
+    /// `(positive, negative)` degree of every node; their sum is the degree
+    ///
+    /// Each pair's sign is read from the upper triangle, as the counters do,
+    /// so both endpoints of an edge see the same sign. Indices line up with
+    /// [`labels`](Self::labels). One O(n²) pass over the signs.
+    pub fn node_degrees(&self) -> Vec<(u32, u32)> {
+        let n = self.n;
+        let mut degrees = vec![(0u32, 0u32); n];
+        for i in 0..n {
+            for j in (i + 1)..n {
+                match self.signs[i * n + j] {
+                    1 => {
+                        degrees[i].0 += 1;
+                        degrees[j].0 += 1;
+                    }
+                    -1 => {
+                        degrees[i].1 += 1;
+                        degrees[j].1 += 1;
+                    }
+                    _ => {}
+                }
+            }
+        }
+        degrees
+    }
+
+    /// Fraction of present edges that are positive (each undirected pair counted once)
+    ///
+    /// Returns NaN when the network has no edges.
+    pub fn positive_fraction(&self) -> f64 {
+        let summary = self.network_summary();
+        summary.positive_edges as f64 / summary.edges() as f64
+    }
+
+    /// Expected category counts if the observed signs were shuffled over the
+    /// observed triangles
+    ///
+    /// With `p` the positive fraction of edges and `T` the number of complete
+    /// triads, a triad has `k` positive edges with binomial probability, giving
+    /// `T * C(3, k) * p^k * (1 - p)^(3 - k)`. Depends only on the sign
+    /// distribution and the triangle count, not on the stored counts; all zero
+    /// when there are no edges. Compare against the observed counts for a
+    /// balance z-score.
+    pub fn expected_counts_random(&self) -> ExpectedTriadCounts {
+        let summary = self.network_summary();
+        if summary.edges() == 0 {
+            return ExpectedTriadCounts::default();
+        }
+
+        let p = summary.positive_edges as f64 / summary.edges() as f64;
+        let q = 1.0 - p;
+        let total = self.closed_triad_count() as f64;
+
+        ExpectedTriadCounts {
+            three_positive: total * p * p * p,
+            two_positive: total * 3.0 * p * p * q,
+            one_positive: total * 3.0 * p * q * q,
+            zero_positive: total * q * q * q,
+        }
+    }
+
+    /// Whether the positive fraction lies outside [`SKEWED_POSITIVE_FRACTION`]
+    ///
+    /// When almost every edge shares one sign, the stable fraction says little
+    /// about balance and is easy to over-interpret.
+    pub fn is_sign_skewed(&self) -> bool {
+        let p = self.positive_fraction();
+        let (low, high) = SKEWED_POSITIVE_FRACTION;
+        p < low || p > high
+    }
+
+    /// Use the parallel strategy for networks with at least `n` nodes
+    /// (default [`DEFAULT_PARALLEL_THRESHOLD`])
+    ///
+    /// Purely a performance knob: both strategies produce identical counts.
+    pub fn set_parallel_threshold(&mut self, n: usize) {
+        self.config.parallel_threshold = n;
+    }
+
+    /// Current parallel-strategy node threshold
+    pub fn parallel_threshold(&self) -> usize {
+        self.config.parallel_threshold
+    }
+
+    /// Strategy that `count_triads_optimized` will use for this network
+    ///
+    /// Networks below [`SPARSE_DENSITY`] use [`CountingStrategy::Sparse`].
+    /// Otherwise the choice is by size, and always
+    /// [`CountingStrategy::Sequential`] without the `parallel` feature.
+    pub fn counting_strategy(&self) -> CountingStrategy {
+        if self.n >= 3 && self.network_summary().density < SPARSE_DENSITY {
+            CountingStrategy::Sparse
+        } else if self.uses_parallel_rows() {
+            CountingStrategy::Parallel
+        } else {
+            CountingStrategy::Sequential
+        }
+    }
+
+    /// Whether row-by-row counts should be spread over rayon's pool
+    fn uses_parallel_rows(&self) -> bool {
+        cfg!(feature = "parallel") && self.n >= self.config.parallel_threshold
+    }
+
+    /// Optimized triad counting using pre-computed signs
+    pub fn count_triads_optimized(&self) -> TriadCounts {
+        let strategy = self.counting_strategy();
+        debug!(
+            "Counting {} nodes with the {} strategy",
+            self.n,
+            strategy.name()
+        );
+        match strategy {
+            CountingStrategy::Parallel => self.count_triads_parallel_chunked(),
+            CountingStrategy::Sequential => self.count_triads_sequential(),
+            CountingStrategy::Sparse => self.count_triads_sparse(),
+        }
+    }
+
+    /// Triad counting by neighbor-list intersection
+    ///
+    /// Packs the signs into a [`SparseSigns`] and counts that, so the cost
+    /// grows with edges and shared neighbors instead of `n³`; much faster
+    /// than the dense loops on sparse networks, and slower on dense ones.
+    /// Runs in parallel from the same
+    /// [`parallel_threshold`](Self::set_parallel_threshold) as the dense
+    /// loops.
+    pub fn count_triads_sparse(&self) -> TriadCounts {
+        SparseSigns::from_plugin(self).count_triads_with(self.uses_parallel_rows())
+    }
+
+    /// Sequential triad counting with pre-computed signs
+    pub fn count_triads_sequential(&self) -> TriadCounts {
+        count_triads_from_signs(&self.signs, self.n)
+    }
+
+    /// Parallel triad counting with chunked workload
+    ///
+    /// Work is split over `(i, j)` pairs rather than just the outer index: each
+    /// row `i` is itself a parallel iterator over `j`, so rayon's work stealing
+    /// can spread a hub row (many present edges, `O(n²)` work) across threads
+    /// instead of leaving one thread to finish it alone.
+    ///
+    /// Without the `parallel` feature this is the same as
+    /// [`count_triads_sequential`](Self::count_triads_sequential).
+    pub fn count_triads_parallel_chunked(&self) -> TriadCounts {
+        count_triads_from_signs_parallel(&self.signs, self.n)
+    }
+
+    /// Count triads, periodically reporting `(rows_done, total_rows)` to `progress`
+    ///
+    /// A row is one outer node index. Progress is reported about every 1% of
+    /// rows, and always once at completion, so the callback stays cheap even
+    /// when shared by all threads of the parallel strategy. Rows finish out of
+    /// order in parallel, but `rows_done` only ever counts completed rows.
+    pub fn count_triads_with_progress<F: Fn(usize, usize) + Sync>(
+        &self,
+        progress: F,
+    ) -> TriadCounts {
+        let n = self.n;
+        let step = (n / 100).max(1);
+        let done = AtomicUsize::new(0);
+        let finish_row = || {
+            let rows_done = done.fetch_add(1, Ordering::Relaxed) + 1;
+            if rows_done.is_multiple_of(step) || rows_done == n {
+                progress(rows_done, n);
+            }
+        };
+
+        let parallel = self.uses_parallel_rows();
+        sum_rows(n, parallel, |i| {
+            let mut counts = TriadCounts::default();
+            count_signs_row(&self.signs, n, i, &mut counts);
+            finish_row();
+            counts
+        })
+    }
+
+    /// Count triads unless `cancel` is set before the count finishes
+    ///
+    /// The flag is checked before each `(i, j)` pair, so a request is noticed
+    /// within one inner loop of `n` nodes; on the parallel strategy every
+    /// thread checks it and skips its remaining rows. Returns `None` if any
+    /// pair was skipped, discarding the partial counts, and the full counts
+    /// otherwise. The flag is only read, so the caller resets it.
+    pub fn count_triads_cancellable(&self, cancel: &AtomicBool) -> Option<TriadCounts> {
+        let n = self.n;
+        let abandoned = AtomicBool::new(false);
+
+        let counts = sum_rows(n, self.uses_parallel_rows(), |i| {
+            let mut counts = TriadCounts::default();
+            for j in (i + 1)..n {
+                if cancel.load(Ordering::Relaxed) {
+                    abandoned.store(true, Ordering::Relaxed);
+                    break;
+                }
+                count_signs_pair(&self.signs, n, i, j, &mut counts);
+            }
+            counts
+        });
+
+        if abandoned.into_inner() {
+            None
+        } else {
+            Some(counts)
+        }
+    }
+
+    /// Count triads in blocks of `every` rows, saving a [`CountCheckpoint`]
+    /// to `path` after each block
+    ///
+    /// Starts from scratch, replacing any checkpoint at `path`; if the process
+    /// is killed, [`resume_from_checkpoint`](Self::resume_from_checkpoint)
+    /// continues from the last saved block. Blocks are always counted over
+    /// the dense sign matrix, even where the
+    /// [`counting_strategy`](Self::counting_strategy) would pick
+    /// [`CountingStrategy::Sparse`], since only its rows split into
+    /// resumable blocks; above the
+    /// [`parallel_threshold`](Self::set_parallel_threshold) each block runs in
+    /// parallel, so blocks should span many rows to keep all threads busy.
+    /// The last checkpoint written is complete and holds the returned counts.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `every` is 0.
+    pub fn count_triads_checkpointed<P: AsRef<Path>>(
+        &self,
+        path: P,
+        every: usize,
+    ) -> Result<TriadCounts, TriadError> {
+        let start = CountCheckpoint {
+            node_count: self.n,
+            next_row: 0,
+            counts: TriadCounts::default(),
+        };
+        self.continue_checkpointed(path.as_ref(), every, start)
+    }
+
+    /// Continue a [`count_triads_checkpointed`](Self::count_triads_checkpointed)
+    /// run from the checkpoint at `path`
+    ///
+    /// Rows the checkpoint already covers are skipped, and checkpoints keep
+    /// being saved every `every` rows; a complete checkpoint just returns its
+    /// counts. Fails with [`TriadError::Malformed`] if the checkpoint was made
+    /// for a different node count. Nothing else about the network is
+    /// recorded, so resuming against a different network of the same size
+    /// silently gives a wrong total.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `every` is 0.
+    pub fn resume_from_checkpoint<P: AsRef<Path>>(
+        &self,
+        path: P,
+        every: usize,
+    ) -> Result<TriadCounts, TriadError> {
+        let path = path.as_ref();
+        let checkpoint = CountCheckpoint::load(path)?;
+        if checkpoint.node_count != self.n {
+            return Err(TriadError::Malformed {
+                line: 2,
+                message: format!(
+                    "checkpoint is for {} nodes but the network has {}",
+                    checkpoint.node_count, self.n
+                ),
+            });
+        }
+        self.continue_checkpointed(path, every, checkpoint)
+    }
+
+    /// Count the rows from `checkpoint.next_row` on, saving after each block
+    fn continue_checkpointed(
+        &self,
+        path: &Path,
+        every: usize,
+        mut checkpoint: CountCheckpoint,
+    ) -> Result<TriadCounts, TriadError> {
+        assert!(every > 0, "checkpoint interval must be at least one row");
+        let n = self.n;
+        let parallel = self.uses_parallel_rows();
+
+        while !checkpoint.is_complete() {
+            let start = checkpoint.next_row;
+            let end = (start + every).min(n);
+            let block = sum_rows(end - start, parallel, |offset| {
+                let mut counts = TriadCounts::default();
+                count_signs_row(&self.signs, n, start + offset, &mut counts);
+                counts
+            });
+
+            checkpoint.counts.merge(&block);
+            checkpoint.next_row = end;
+            checkpoint.save(path)?;
+            debug!("Checkpointed {} of {} rows", end, n);
+        }
+
+        Ok(checkpoint.counts)
+    }
+
+    /// Sparsify to each node's `k` strongest edges, returning a new plugin
+    ///
+    /// For every node the `k` present edges with the largest `|weight|` are
+    /// selected (ties go to the lower neighbor index). An edge survives if
+    /// either endpoint selected it, and survivors are written symmetrically
+    /// using the upper-triangle weight; dropped pairs get the missing-edge
+    /// value, so they stay absent under a sentinel. This changes triad counts
+    /// and is meant as an approximation for very dense weighted networks.
+    pub fn sparsify_topk(&self, k: usize) -> TriadCounterPlugin {
+        self.require_weights("sparsify_topk");
+        let n = self.n;
+        let pair_weight = |i: usize, j: usize| {
+            let (a, b) = if i < j { (i, j) } else { (j, i) };
+            (self.adj[a * n + b], self.signs[a * n + b])
+        };
+
+        let mut keep = vec![false; n * n];
+        let mut candidates: Vec<(usize, f64)> = Vec::with_capacity(n);
+        for i in 0..n {
+            candidates.clear();
+            candidates.extend((0..n).filter(|&j| j != i).filter_map(|j| {
+                let (w, sign) = pair_weight(i, j);
+                (sign != 0).then_some((j, w.abs()))
+            }));
+            candidates.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+            for &(j, _) in candidates.iter().take(k) {
+                keep[i * n + j] = true;
+                keep[j * n + i] = true;
+            }
+        }
+
+        let mut adj = absent_matrix(n, self.config.missing);
+        for i in 0..n {
+            for j in (i + 1)..n {
+                if keep[i * n + j] {
+                    let (w, _) = pair_weight(i, j);
+                    adj[i * n + j] = w;
+                    adj[j * n + i] = w;
+                }
+            }
+        }
+
+        let mut sparse = Self {
+            adj,
+            signs: Vec::new(),
+            n,
+            labels: self.labels.clone(),
+            label_index: self.label_index.clone(),
+            counts: TriadCounts::default(),
+            counts_current: false,
+            original_adj: None,
+            config: self.config.clone(),
+        };
+        sparse.compute_signs();
+        sparse
+    }
+
+    /// Sign of the unordered pair as seen by the counters (upper triangle)
+    #[inline(always)]
+    fn pair_sign(&self, a: usize, b: usize) -> i8 {
+        if a < b {
+            self.signs[a * self.n + b]
+        } else {
+            self.signs[b * self.n + a]
+        }
+    }
+
+    /// Classify the triad on nodes `i`, `j`, `k`, given in any order
+    ///
+    /// Returns `None` unless all three edges are present (so also when two
+    /// indices coincide), using the same upper-triangle signs as the counters.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any index is out of range.
+    pub fn classify_triad(&self, i: usize, j: usize, k: usize) -> Option<TriadType> {
+        let n = self.n;
+        assert!(
+            i < n && j < n && k < n,
+            "triad ({}, {}, {}) out of range for {} nodes",
+            i,
+            j,
+            k,
+            n
+        );
+
+        let (ij, ik, jk) = (
+            self.pair_sign(i, j),
+            self.pair_sign(i, k),
+            self.pair_sign(j, k),
+        );
+        if ij == 0 || ik == 0 || jk == 0 {
+            return None;
+        }
+        let pos_count = ((ij > 0) as u8) + ((ik > 0) as u8) + ((jk > 0) as u8);
+        Some(TriadType::from_positive_count(pos_count))
+    }
+
+    /// Counts of the complete triads containing the edge `(i, j)`
+    ///
+    /// Scans every third node `k` once, so this is O(n): a quick way to see
+    /// which triads would change if the edge were flipped or removed. All
+    /// zeros when the pair has no edge, including `i == j`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` or `j` is out of range.
+    pub fn triads_for_edge(&self, i: usize, j: usize) -> TriadCounts {
+        assert!(
+            i < self.n && j < self.n,
+            "edge ({}, {}) out of range for {} nodes",
+            i,
+            j,
+            self.n
+        );
+
+        let mut counts = TriadCounts::default();
+        let ij = self.pair_sign(i, j);
+        if ij == 0 {
+            return counts;
+        }
+
+        for k in (0..self.n).filter(|&k| k != i && k != j) {
+            let ik = self.pair_sign(i, k);
+            let jk = self.pair_sign(j, k);
+            if ik == 0 || jk == 0 {
+                continue;
+            }
+            let pos_count = ((ij > 0) as u8) + ((ik > 0) as u8) + ((jk > 0) as u8);
+            counts.record(TriadType::from_positive_count(pos_count));
+        }
+
+        counts
+    }
+
+    /// Set the weight of edge `(i, j)` and incrementally update the stored counts
+    ///
+    /// Writes `value` to both directions, subtracts the triads the old edge
+    /// closed and adds those the new one closes, all in O(n) instead of a full
+    /// recount. The sign honours the current threshold. The delta needs
+    /// current counts, from `run()` or previous incremental updates; before
+    /// the first `run()`, or after an option change reset the counts, the
+    /// network is recounted in full instead. Returns the updated counts.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` or `j` is out of range, if `i == j`, or if the weights
+    /// were freed with [`free_adjacency`](Self::free_adjacency).
+    pub fn update_edge(&mut self, i: usize, j: usize, value: f64) -> TriadCounts {
+        self.require_weights("update_edge");
+        let n = self.n;
+        assert!(
+            i < n && j < n,
+            "edge ({}, {}) out of range for {} nodes",
+            i,
+            j,
+            n
+        );
+        assert_ne!(i, j, "self-loops are not edges");
+
+        let removed = self.triads_for_edge(i, j);
+        let sign = Self::to_sign_with_policy(value, self.config.threshold, self.config.missing);
+        self.original_adj = None;
+        self.adj[i * n + j] = value;
+        self.adj[j * n + i] = value;
+        self.signs[i * n + j] = sign;
+        self.signs[j * n + i] = sign;
+
+        if self.counts_current && self.counts.unmerge(&removed) {
+            let added = self.triads_for_edge(i, j);
+            self.counts.merge(&added);
+        } else {
+            self.counts = self.count_triads_optimized();
+            self.counts_current = true;
+        }
+
+        self.counts.clone()
+    }
+
+    /// Remove the edge `(i, j)` and incrementally update the stored counts
+    ///
+    /// Equivalent to `update_edge` with the missing-edge value (0.0 unless a
+    /// sentinel is configured), including the full recount when the stored
+    /// counts are not current.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` or `j` is out of range, if `i == j`, or if the weights
+    /// were freed with [`free_adjacency`](Self::free_adjacency).
+    pub fn remove_edge(&mut self, i: usize, j: usize) -> TriadCounts {
+        self.update_edge(i, j, self.config.missing.absent_value())
+    }
+
+    /// Drop the given nodes, e.g. placeholder nodes that should not take part
+    /// in the analysis
+    ///
+    /// The remaining nodes keep their relative order and are renumbered
+    /// `0..n'`, so an index refers to a different node afterwards; look nodes
+    /// up again with [`label_index`](Self::label_index). Weights and signs are
+    /// carried over unchanged, repeated indices are ignored, and the stored
+    /// counts are reset.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any index is out of range.
+    pub fn remove_nodes(&mut self, indices: &[usize]) {
+        let n = self.n;
+        let mut removed = vec![false; n];
+        for &idx in indices {
+            assert!(idx < n, "node {} out of range for {} nodes", idx, n);
+            removed[idx] = true;
+        }
+        let kept: Vec<usize> = (0..n).filter(|&i| !removed[i]).collect();
+        let m = kept.len();
+
+        let mut adj = Vec::with_capacity(m * m);
+        let mut signs = Vec::with_capacity(m * m);
+        let has_adjacency = self.has_adjacency();
+        for &i in &kept {
+            if has_adjacency {
+                adj.extend(kept.iter().map(|&j| self.adj[i * n + j]));
+            }
+            signs.extend(kept.iter().map(|&j| self.signs[i * n + j]));
+        }
+        let labels = kept.iter().map(|&i| self.labels[i].clone()).collect();
+
+        self.adj = adj;
+        self.original_adj = None;
+        self.signs = signs;
+        self.n = m;
+        self.set_labels(labels);
+        self.reset_counts();
+    }
+
+    /// Number of complete triangles, without classifying their signs
+    ///
+    /// Builds a bitset of upper-triangle edge presence per node and sums the
+    /// popcount of `row_i & row_j` over every edge `i < j`, which is much
+    /// cheaper than the four-way classification when only `total()` is needed.
+    pub fn triangle_count_fast(&self) -> u64 {
+        let n = self.n;
+        let (words, upper) = self.upper_bitsets(|sign| sign != 0);
+
+        let mut total = 0u64;
+        for i in 0..n {
+            let row_i = &upper[i * words..(i + 1) * words];
+            for j in (i + 1)..n {
+                if row_i[j / 64] & (1 << (j % 64)) == 0 {
+                    continue;
+                }
+                let row_j = &upper[j * words..(j + 1) * words];
+                total += and_popcount(row_i, row_j);
+            }
+        }
+
+        total
+    }
+
+    /// Number of wedges: pairs of edges sharing a vertex, `sum_v C(deg(v), 2)`
+    ///
+    /// Only edge presence matters; signs are ignored, so a wedge of two
+    /// negative ties counts the same as one of two positive ties. Every wedge
+    /// is either closed by a third edge or open; a triangle closes three
+    /// wedges, so open wedges are `wedge_count() - 3 * closed_triad_count()`
+    /// and the transitivity is `3 * closed_triad_count() / wedge_count()`.
+    pub fn wedge_count(&self) -> u64 {
+        let n = self.n;
+        (0..n)
+            .map(|v| {
+                let degree = (0..n)
+                    .filter(|&u| u != v && self.pair_sign(u, v) != 0)
+                    .count() as u64;
+                degree * degree.saturating_sub(1) / 2
+            })
+            .sum()
+    }
+
+    /// Number of closed triads (triangles with all three edges present)
+    ///
+    /// Like [`wedge_count`](Self::wedge_count) this ignores signs; it equals
+    /// `total()` of the classified counts but is computed directly.
+    pub fn closed_triad_count(&self) -> u64 {
+        self.triangle_count_fast()
+    }
+
+    /// Rows of upper-triangle bits: bit `k` of row `i` is set when `k > i` and
+    /// `keep(signs[i][k])`. Returns the words per row and the flat bitset.
+    fn upper_bitsets<F: Fn(i8) -> bool>(&self, keep: F) -> (usize, Vec<u64>) {
+        let n = self.n;
+        let words = n.div_ceil(64);
+        let mut upper = vec![0u64; n * words];
+
+        for i in 0..n {
+            let row = &mut upper[i * words..(i + 1) * words];
+            for (k, &sign) in self.signs[i * n..(i + 1) * n]
+                .iter()
+                .enumerate()
+                .skip(i + 1)
+            {
+                if keep(sign) {
+                    row[k / 64] |= 1 << (k % 64);
+                }
+            }
+        }
+
+        (words, upper)
+    }
+
+    /// Sequential triad counting with a vectorized inner loop
+    ///
+    /// For each edge `i < j`, the rows `signs[i][j+1..]` and `signs[j][j+1..]`
+    /// are classified in fixed-width chunks of branch-free compares that the
+    /// compiler turns into SIMD instructions, instead of one `k` at a time.
+    /// Produces the same counts as
+    /// [`count_triads_sequential`](Self::count_triads_sequential) and pays off
+    /// on dense graphs; sparse rows gain little since most work is skipped
+    /// at the `i-j` check anyway.
+    pub fn count_triads_simd(&self) -> TriadCounts {
+        let n = self.n;
+        let mut counts = TriadCounts::default();
+        for i in 0..n {
+            for j in (i + 1)..n {
+                count_signs_pair_simd(&self.signs, n, i, j, &mut counts);
+            }
+        }
+        counts
+    }
+
+    /// Count triads with positive/negative bitsets instead of per-`k` branches
+    ///
+    /// Each node gets a positive and a negative row of upper-triangle
+    /// neighbors. For an edge `i < j`, the third nodes `k > j` fall into four
+    /// classes by the signs of `i-k` and `j-k`, and each class is a popcount
+    /// of an AND of two rows; the sign of `i-j` then maps them to categories.
+    /// Produces the same counts as
+    /// [`count_triads_sequential`](Self::count_triads_sequential) and is
+    /// several times faster on dense graphs; it costs two extra `n * n / 8`
+    /// byte bitsets.
+    pub fn count_triads_bitset(&self) -> TriadCounts {
+        let n = self.n;
+        let (words, pos) = self.upper_bitsets(|sign| sign > 0);
+        let (_, neg) = self.upper_bitsets(|sign| sign < 0);
+        let row = |i: usize| i * words..(i + 1) * words;
+
+        let mut counts = TriadCounts::default();
+        for i in 0..n {
+            let (pos_i, neg_i) = (&pos[row(i)], &neg[row(i)]);
+            for j in (i + 1)..n {
+                let ij = self.signs[i * n + j];
+                if ij == 0 {
+                    continue;
+                }
+                let (pos_j, neg_j) = (&pos[row(j)], &neg[row(j)]);
+
+                // Rows only hold k beyond their own node, so these are k > j
+                let both_pos = and_popcount(pos_i, pos_j);
+                let mixed = and_popcount(pos_i, neg_j) + and_popcount(neg_i, pos_j);
+                let both_neg = and_popcount(neg_i, neg_j);
+
+                if ij > 0 {
+                    counts.three_positive += both_pos;
+                    counts.two_positive += mixed;
+                    counts.one_positive += both_neg;
+                } else {
+                    counts.two_positive += both_pos;
+                    counts.one_positive += mixed;
+                    counts.zero_positive += both_neg;
+                }
+            }
+        }
+
+        counts
+    }
+
+    /// Count triads at each edge-presence threshold
+    ///
+    /// For every threshold `t`, signs are recomputed from the stored weights
+    /// with `|w| <= t` treated as no edge, and triads are counted. The plugin's
+    /// own signs and counts are left untouched.
+    pub fn threshold_sweep(&self, thresholds: &[f64]) -> Vec<(f64, TriadCounts)> {
+        thresholds
+            .iter()
+            .map(|&t| (t, self.count_at_threshold(t)))
+            .collect()
+    }
+
+    /// Like [`threshold_sweep`](Self::threshold_sweep), but thresholds are
+    /// counted concurrently when the `parallel` feature is enabled
+    ///
+    /// Results stay in the order of `thresholds`. Each threshold holds its own
+    /// `n * n` sign matrix while it is counted, so memory grows with the
+    /// number of threads.
+    pub fn balance_sweep(&self, thresholds: &[f64]) -> Vec<(f64, TriadCounts)> {
+        #[cfg(feature = "parallel")]
+        {
+            thresholds
+                .par_iter()
+                .map(|&t| (t, self.count_at_threshold(t)))
+                .collect()
+        }
+        #[cfg(not(feature = "parallel"))]
+        self.threshold_sweep(thresholds)
+    }
+
+    /// Count triads among strong ties only: an edge counts when its weight
+    /// has `|w| >= min_abs`
+    ///
+    /// The filter applies to the stored upper-triangle weight on top of the
+    /// usual sign options, so an edge must be present in the current signs
+    /// and strong enough. Unlike [`set_threshold`](Self::set_threshold), the
+    /// plugin is left untouched, so strong- and weak-tie balance can be
+    /// compared without reloading.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the weights were freed with
+    /// [`free_adjacency`](Self::free_adjacency).
+    pub fn count_triads_min_weight(&self, min_abs: f64) -> TriadCounts {
+        self.require_weights("count_triads_min_weight");
+        let n = self.n;
+        count_triads_with_sign_fn(n, |i, j| {
+            if self.adj[i * n + j].abs() >= min_abs {
+                self.signs[i * n + j]
+            } else {
+                0
+            }
+        })
+    }
+
+    /// Sequential count with signs recomputed at threshold `t`
+    fn count_at_threshold(&self, t: f64) -> TriadCounts {
+        self.require_weights("threshold sweeps");
+        let mut signs = Vec::new();
+        Self::signs_into(&self.adj, self.n, &self.config, t, &mut signs);
+        count_triads_from_signs(&signs, self.n)
+    }
+
+    /// Area under the balance-ratio-vs-threshold curve, normalized by the range
+    ///
+    /// Runs [`threshold_sweep`](Self::threshold_sweep), computes
+    /// `stable / total` at each threshold, and integrates with the trapezoidal
+    /// rule over the sorted thresholds, dividing by `max - min`. The result is
+    /// the mean balance across the range. Thresholds leaving no triads are
+    /// skipped; with a single usable threshold its ratio is returned, and with
+    /// none the result is NaN.
+    pub fn integrated_balance(&self, thresholds: &[f64]) -> f64 {
+        let mut curve: Vec<(f64, f64)> = self
+            .threshold_sweep(thresholds)
+            .into_iter()
+            .filter(|(_, counts)| counts.total() > 0)
+            .map(|(t, counts)| (t, counts.stable() as f64 / counts.total() as f64))
+            .collect();
+        curve.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        match curve.as_slice() {
+            [] => f64::NAN,
+            [(_, ratio)] => *ratio,
+            [first, .., last] if last.0 == first.0 => {
+                curve.iter().map(|(_, r)| r).sum::<f64>() / curve.len() as f64
+            }
+            [first, .., last] => {
+                let area: f64 = curve
+                    .windows(2)
+                    .map(|w| (w[1].0 - w[0].0) * (w[0].1 + w[1].1) / 2.0)
+                    .sum();
+                area / (last.0 - first.0)
+            }
+        }
+    }
+
+    /// Expected triad counts after imputing absent edges from their neighborhoods
+    ///
+    /// Each absent pair `(i, j)` looks at its shared neighbors `k` (nodes tied
+    /// to both) and imputes a sign from the observed ties `i-k` and `j-k`
+    /// according to `method`; pairs without shared neighbors stay absent.
+    /// Imputation uses only the observed network, and imputed edges are
+    /// treated as independent. The result is a model-based estimate, not an
+    /// observation.
+    pub fn count_triads_imputed(&self, method: ImputationMethod) -> ExpectedTriadCounts {
+        let n = self.n;
+        let mut presence = vec![0.0; n * n];
+        let mut positive = vec![0.0; n * n];
+
+        for i in 0..n {
+            for j in (i + 1)..n {
+                let idx = i * n + j;
+                let sign = self.pair_sign(i, j);
+                if sign != 0 {
+                    presence[idx] = 1.0;
+                    positive[idx] = (sign > 0) as u8 as f64;
+                    continue;
+                }
+
+                let (mut pos, mut neg) = (0u32, 0u32);
+                for k in (0..n).filter(|&k| k != i && k != j) {
+                    let (ik, jk) = (self.pair_sign(i, k), self.pair_sign(j, k));
+                    if ik == 0 || jk == 0 {
+                        continue;
+                    }
+                    for sign in [ik, jk] {
+                        if sign > 0 {
+                            pos += 1;
+                        } else {
+                            neg += 1;
+                        }
+                    }
+                }
+                if pos + neg == 0 {
+                    continue;
+                }
+
+                match method {
+                    ImputationMethod::NeighborMajority if pos != neg => {
+                        presence[idx] = 1.0;
+                        positive[idx] = (pos > neg) as u8 as f64;
+                    }
+                    ImputationMethod::NeighborMajority => {}
+                    ImputationMethod::NeighborFrequency => {
+                        presence[idx] = 1.0;
+                        positive[idx] = pos as f64 / (pos + neg) as f64;
+                    }
+                }
+            }
+        }
+
+        expected_counts_from_probabilities(n, &presence, &positive)
+    }
+
+    /// Expected triad counts when each weight is the probability that its
+    /// edge is positive
+    ///
+    /// Every present pair `i < j` is positive with probability `adj[i][j]`
+    /// (clamped to `[0, 1]`) and negative otherwise, independently of the
+    /// other pairs; each triangle contributes its probability of falling in
+    /// each category. Presence follows the loaded signs, so load
+    /// probabilities with [`MissingEdgePolicy::Sentinel`] for the absent value
+    /// and a threshold of 0.5: the hard counts from [`run`](Self::run) then
+    /// call a pair positive above 0.5 and negative otherwise.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the weights were freed with
+    /// [`free_adjacency`](Self::free_adjacency).
+    pub fn expected_counts_probabilistic(&self) -> ExpectedTriadCounts {
+        self.require_weights("expected_counts_probabilistic");
+        let n = self.n;
+        let mut presence = vec![0.0; n * n];
+        let mut positive = vec![0.0; n * n];
+        for i in 0..n {
+            for j in (i + 1)..n {
+                let idx = i * n + j;
+                if self.signs[idx] != 0 {
+                    presence[idx] = 1.0;
+                    positive[idx] = self.adj[idx].clamp(0.0, 1.0);
+                }
+            }
+        }
+
+        expected_counts_from_probabilities(n, &presence, &positive)
+    }
+
+    /// Invoke `f(i, j, k, triad_type)` for every complete triad
+    ///
+    /// Triads are visited in increasing `(i, j, k)` order with `i < j < k`, without
+    /// any intermediate allocation.
+    pub fn for_each_triad<F: FnMut(usize, usize, usize, TriadType)>(&self, mut f: F) {
+        for i in 0..self.n {
+            self.for_each_triad_in_row(i, &mut f);
+        }
+    }
+
+    /// [`for_each_triad`](Self::for_each_triad) restricted to triads whose
+    /// smallest node is `i`
+    fn for_each_triad_in_row<F: FnMut(usize, usize, usize, TriadType)>(&self, i: usize, mut f: F) {
+        let n = self.n;
+        let i_offset = i * n;
+        for j in (i + 1)..n {
+            let ij = self.signs[i_offset + j];
+            if ij == 0 {
+                continue;
+            }
+
+            let j_offset = j * n;
+            for k in (j + 1)..n {
+                let ik = self.signs[i_offset + k];
+                let jk = self.signs[j_offset + k];
+
+                if ik == 0 || jk == 0 {
+                    continue;
+                }
+
+                let pos_count = ((ij > 0) as u8) + ((ik > 0) as u8) + ((jk > 0) as u8);
+                f(i, j, k, TriadType::from_positive_count(pos_count));
+            }
+        }
+    }
+
+    /// Whether no complete triangle is unstable
+    ///
+    /// This is the triangle-level check; a network can pass it and still
+    /// contain an unbalanced longer cycle. For the full structural-balance
+    /// test see [`structural_balance_partition`](Self::structural_balance_partition).
+    pub fn is_balanced(&self) -> bool {
+        let mut balanced = true;
+        self.for_each_triad(|_, _, _, t| balanced &= t.is_stable());
+        balanced
+    }
+
+    /// Split the nodes into two antagonistic groups, if the network allows it
+    ///
+    /// By Harary's theorem a signed network is structurally balanced exactly
+    /// when its nodes can be 2-colored so positive edges join equal colors and
+    /// negative edges join different ones. A breadth-first search over each
+    /// connected component tries such a coloring, returning each node's group
+    /// (0 or 1) or `None` at the first contradiction. Each component's first
+    /// node is placed in group 0, and isolated nodes stay in group 0. Runs in
+    /// O(n²).
+    pub fn structural_balance_partition(&self) -> Option<Vec<usize>> {
+        let n = self.n;
+        let mut group: Vec<Option<usize>> = vec![None; n];
+        let mut queue = std::collections::VecDeque::new();
+
+        for start in 0..n {
+            if group[start].is_some() {
+                continue;
+            }
+            group[start] = Some(0);
+            queue.push_back(start);
+
+            while let Some(u) = queue.pop_front() {
+                let gu = group[u].expect("queued nodes are colored");
+                for v in (0..n).filter(|&v| v != u) {
+                    let sign = self.pair_sign(u, v);
+                    if sign == 0 {
+                        continue;
+                    }
+                    let wanted = if sign > 0 { gu } else { 1 - gu };
+                    match group[v] {
+                        Some(gv) if gv != wanted => return None,
+                        Some(_) => {}
+                        None => {
+                            group[v] = Some(wanted);
+                            queue.push_back(v);
+                        }
+                    }
+                }
+            }
+        }
+
+        Some(group.into_iter().map(|g| g.unwrap_or(0)).collect())
+    }
+
+    /// Count 4-cycles whose sign product is negative (unbalanced)
+    ///
+    /// A 4-cycle `a-b-c-d-a` needs all four edges present, regardless of the
+    /// chords `a-c` and `b-d`. For each pair of opposite corners the two-step
+    /// paths through a common neighbor are split by sign product; pairing a
+    /// positive path with a negative one closes an unbalanced cycle. Every cycle
+    /// has two diagonals, so the pair total is halved. Runs in O(n³).
+    pub fn count_unbalanced_4cycles(&self) -> u64 {
+        let n = self.n;
+        let mut twice = 0u64;
+
+        for a in 0..n {
+            for c in (a + 1)..n {
+                let (pos, neg) = self.signed_paths_of_length_two(a, c);
+                twice += pos * neg;
+            }
+        }
+
+        twice / 2
+    }
+
+    /// Whether at least one 4-cycle has a negative sign product
+    pub fn has_unbalanced_4cycle(&self) -> bool {
+        let n = self.n;
+        (0..n).any(|a| {
+            ((a + 1)..n).any(|c| {
+                let (pos, neg) = self.signed_paths_of_length_two(a, c);
+                pos > 0 && neg > 0
+            })
+        })
+    }
+
+    /// Number of `a-b-c` paths with positive and negative sign product
+    fn signed_paths_of_length_two(&self, a: usize, c: usize) -> (u64, u64) {
+        let n = self.n;
+        let a_row = &self.signs[a * n..(a + 1) * n];
+        let c_row = &self.signs[c * n..(c + 1) * n];
+        let mut pos = 0u64;
+        let mut neg = 0u64;
+
+        for (&ab, &cb) in a_row.iter().zip(c_row) {
+            match ab * cb {
+                1 => pos += 1,
+                -1 => neg += 1,
+                _ => {}
+            }
+        }
+
+        (pos, neg)
+    }
+
+    /// Lazily iterate complete triads as `(i, j, k, positive_edges)`
+    ///
+    /// Only triangles with all three edges nonzero are yielded, in increasing
+    /// `(i, j, k)` order with `i < j < k`. Nothing is collected up front, so
+    /// this is suitable for graphs with millions of triads.
+    pub fn enumerate_triads(&self) -> impl Iterator<Item = (usize, usize, usize, u8)> + '_ {
+        TriadIter {
+            signs: &self.signs,
+            n: self.n,
+            i: 0,
+            j: 1,
+            k: 2,
+        }
+    }
+
+    /// Lazily yield the labels of every unstable (two- or zero-positive) triad
+    ///
+    /// Same order as [`enumerate_triads`](Self::enumerate_triads); the labels
+    /// are borrowed, so nothing is allocated per triad.
+    pub fn unstable_triads(&self) -> impl Iterator<Item = [&str; 3]> + '_ {
+        self.enumerate_triads()
+            .filter(|&(_, _, _, pos_count)| !TriadType::from_positive_count(pos_count).is_stable())
+            .map(|(i, j, k, _)| {
+                [
+                    self.labels[i].as_str(),
+                    self.labels[j].as_str(),
+                    self.labels[k].as_str(),
+                ]
+            })
+    }
+
+    /// Collect every complete triad as `(i, j, k, triad_type)`
+    ///
+    /// The result is always in strictly increasing lexicographic `(i, j, k)`
+    /// order with `i < j < k`, so listings are reproducible and diffable
+    /// across runs.
+    pub fn list_triads(&self) -> Vec<(usize, usize, usize, TriadType)> {
+        let mut triads = Vec::new();
+        self.for_each_triad(|i, j, k, t| triads.push((i, j, k, t)));
+        triads
+    }
+
+    /// Collect the `(i, j, k)` triples of one triad type, in the same order as
+    /// [`list_triads`](Self::list_triads)
+    pub fn list_triads_of_type(&self, triad_type: TriadType) -> Vec<(usize, usize, usize)> {
+        let mut triads = Vec::new();
+        self.for_each_triad(|i, j, k, t| {
+            if t == triad_type {
+                triads.push((i, j, k));
+            }
+        });
+        triads
+    }
+
+    /// Count only the triads whose three nodes all lie in `nodes`
+    ///
+    /// Duplicate indices are ignored and order does not matter. Runs in
+    /// O(m³) for `m` distinct nodes, without building a sub-matrix.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any index is `>= node_count()`.
+    pub fn count_triads_subset(&self, nodes: &[usize]) -> TriadCounts {
+        let mut nodes = nodes.to_vec();
+        nodes.sort_unstable();
+        nodes.dedup();
+        if let Some(&last) = nodes.last() {
+            assert!(
+                last < self.n,
+                "node {} out of range for {} nodes",
+                last,
+                self.n
+            );
+        }
+
+        let mut counts = TriadCounts::default();
+        for (a, &i) in nodes.iter().enumerate() {
+            for (b, &j) in nodes.iter().enumerate().skip(a + 1) {
+                let ij = self.pair_sign(i, j);
+                if ij == 0 {
+                    continue;
+                }
+                for &k in &nodes[b + 1..] {
+                    let ik = self.pair_sign(i, k);
+                    let jk = self.pair_sign(j, k);
+                    if ik == 0 || jk == 0 {
+                        continue;
+                    }
+                    let pos_count = ((ij > 0) as u8) + ((ik > 0) as u8) + ((jk > 0) as u8);
+                    counts.record(TriadType::from_positive_count(pos_count));
+                }
+            }
+        }
+
+        counts
+    }
+
+    /// Sum of edge-weight products `w_ij * w_ik * w_jk` over complete triangles
+    ///
+    /// Triangles are the same ones the counters see (all three signs nonzero),
+    /// but the products use the stored weights, giving a continuous balance
+    /// measure: positive terms are balanced, negative ones frustrated. Uses
+    /// Neumaier compensated summation to limit rounding error over many terms.
+    pub fn weighted_balance_sum(&self) -> f64 {
+        self.require_weights("weighted_balance_sum");
+        let n = self.n;
+        let (mut sum, mut compensation) = (0.0f64, 0.0f64);
+
+        self.for_each_triad(|i, j, k, _| {
+            let term = self.adj[i * n + j] * self.adj[i * n + k] * self.adj[j * n + k];
+            let t = sum + term;
+            if sum.abs() >= term.abs() {
+                compensation += (sum - t) + term;
+            } else {
+                compensation += (term - t) + sum;
+            }
+            sum = t;
+        });
+
+        sum + compensation
+    }
+
+    /// Count triads separately for each combination of member groups
+    ///
+    /// `groups[i]` is node `i`'s group id, e.g. a community assignment. Each
+    /// complete triangle is counted under the sorted ids of its three nodes,
+    /// so `[g, g, g]` collects within-group triads and `[a, b, b]` those with
+    /// one member outside `b`. Only combinations that occur are present.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `groups.len()` differs from the number of nodes.
+    pub fn count_triads_by_partition(&self, groups: &[usize]) -> HashMap<[usize; 3], TriadCounts> {
+        assert_eq!(
+            groups.len(),
+            self.n,
+            "groups length {} does not match node count {}",
+            groups.len(),
+            self.n
+        );
+
+        let mut by_groups: HashMap<[usize; 3], TriadCounts> = HashMap::new();
+        self.for_each_triad(|i, j, k, triad_type| {
+            let mut key = [groups[i], groups[j], groups[k]];
+            key.sort_unstable();
+            by_groups.entry(key).or_default().record(triad_type);
+        });
+        by_groups
+    }
+
+    /// Count triads weighted by the product of their nodes' importance scores
+    ///
+    /// Each complete triangle `(i, j, k)` contributes
+    /// `importance[i] * importance[j] * importance[k]` to its category instead of 1.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `importance.len()` differs from the number of nodes.
+    pub fn count_triads_node_weighted(&self, importance: &[f64]) -> WeightedTriadCounts {
+        assert_eq!(
+            importance.len(),
+            self.n,
+            "importance length {} does not match node count {}",
+            importance.len(),
+            self.n
+        );
+
+        let mut counts = WeightedTriadCounts::default();
+        let n = self.n;
+
+        for i in 0..n {
+            let i_offset = i * n;
+            for j in (i + 1)..n {
+                let ij = self.signs[i_offset + j];
+                if ij == 0 {
+                    continue;
+                }
+
+                let j_offset = j * n;
+                let w_ij = importance[i] * importance[j];
+                for (k, &imp_k) in importance.iter().enumerate().skip(j + 1) {
+                    let ik = self.signs[i_offset + k];
+                    let jk = self.signs[j_offset + k];
+
+                    if ik == 0 || jk == 0 {
+                        continue;
+                    }
+
+                    let pos_count = ((ij > 0) as u8) + ((ik > 0) as u8) + ((jk > 0) as u8);
+                    counts.record(TriadType::from_positive_count(pos_count), w_ij * imp_k);
+                }
+            }
+        }
+
+        counts
+    }
+
+    /// Write results to output file
+    pub fn output<P: AsRef<Path>>(&self, path: P) -> Result<(), TriadError> {
+        let file = std::fs::File::create(path)?;
+        self.output_to(file)
+    }
+
+    /// Append results to `path`, creating it if needed
+    ///
+    /// Each block is the [`output`](Self::output) format preceded by a
+    /// `Network: <label> (<n> nodes)` line, so results for many networks can
+    /// be collected in one file.
+    pub fn output_append<P: AsRef<Path>>(&self, path: P, label: &str) -> Result<(), TriadError> {
+        use std::io::Write;
+
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+        writeln!(file, "Network: {} ({} nodes)", label, self.n)?;
+        self.output_to(file)
+    }
+
+    /// Write results in the [`output`](Self::output) format to any writer
+    ///
+    /// Useful for stdout, in-memory buffers, or sockets. The writer is not
+    /// buffered here; wrap it in a `BufWriter` if that matters.
+    pub fn output_to<W: std::io::Write>(&self, mut writer: W) -> Result<(), TriadError> {
+        writeln!(writer, "*********************************************")?;
+        writeln!(writer, "{}", self.counts)?;
+        writeln!(writer, "*********************************************")?;
+
+        Ok(())
+    }
+
+    /// Write results as JSON
+    ///
+    /// Includes `node_count`, `labels`, and a `counts` object with the four
+    /// categories plus the derived `stable` and `unstable` totals.
+    pub fn output_json<P: AsRef<Path>>(&self, path: P) -> Result<(), TriadError> {
+        use std::fs::File;
+        use std::io::{BufWriter, Write};
+
+        let mut file = BufWriter::new(File::create(path)?);
+        let labels: Vec<String> = self.labels.iter().map(|l| json_string(l)).collect();
+
+        writeln!(file, "{{")?;
+        writeln!(file, "  \"node_count\": {},", self.n)?;
+        writeln!(file, "  \"labels\": [{}],", labels.join(", "))?;
+        write_counts_json(&mut file, &self.counts)?;
+        writeln!(file, "}}")?;
+        file.flush()?;
+
+        Ok(())
+    }
+
+    /// Write a JSON run report: the input name, node count, elapsed seconds,
+    /// counting strategy, and the same `counts` object as
+    /// [`output_json`](Self::output_json)
+    ///
+    /// Run metadata for automation, kept apart from the analysis results of
+    /// [`output_json`](Self::output_json); the CLI's `--report` flag writes
+    /// it. `input` is recorded as given.
+    pub fn output_run_report<P: AsRef<Path>>(
+        &self,
+        path: P,
+        input: &str,
+        elapsed_seconds: f64,
+    ) -> Result<(), TriadError> {
+        use std::fs::File;
+        use std::io::{BufWriter, Write};
+
+        let mut file = BufWriter::new(File::create(path)?);
+        writeln!(file, "{{")?;
+        writeln!(file, "  \"input\": {},", json_string(input))?;
+        writeln!(file, "  \"node_count\": {},", self.n)?;
+        writeln!(file, "  \"elapsed_seconds\": {},", elapsed_seconds)?;
+        writeln!(
+            file,
+            "  \"strategy\": \"{}\",",
+            self.counting_strategy().name()
+        )?;
+        write_counts_json(&mut file, &self.counts)?;
+        writeln!(file, "}}")?;
+        file.flush()?;
+
+        Ok(())
+    }
+
+    /// Per-node triad counts: entry `i` covers every triad containing node `i`
+    ///
+    /// Indices line up with [`labels`](Self::labels). Every triad contributes
+    /// to three entries, so the per-node totals sum to `3 * total()`.
+    pub fn count_triads_per_node(&self) -> Vec<TriadCounts> {
+        let mut per_node = vec![TriadCounts::default(); self.n];
+        self.for_each_triad(|i, j, k, triad_type| {
+            per_node[i].record(triad_type);
+            per_node[j].record(triad_type);
+            per_node[k].record(triad_type);
+        });
+        per_node
+    }
+
+    /// Parallel form of [`count_triads_per_node`](Self::count_triads_per_node)
+    ///
+    /// Outer indices are spread over rayon's pool; a triad touches three
+    /// entries, so every worker accumulates into its own `n`-entry vector and
+    /// the vectors are added element-wise at the end, costing one extra
+    /// vector per thread. Without the `parallel` feature this is the
+    /// sequential version.
+    pub fn count_triads_per_node_parallel(&self) -> Vec<TriadCounts> {
+        #[cfg(feature = "parallel")]
+        {
+            let n = self.n;
+            (0..n)
+                .into_par_iter()
+                .fold(
+                    || vec![TriadCounts::default(); n],
+                    |mut per_node, i| {
+                        self.for_each_triad_in_row(i, |i, j, k, triad_type| {
+                            per_node[i].record(triad_type);
+                            per_node[j].record(triad_type);
+                            per_node[k].record(triad_type);
+                        });
+                        per_node
+                    },
+                )
+                .reduce(
+                    || vec![TriadCounts::default(); n],
+                    |mut a, b| {
+                        for (total, part) in a.iter_mut().zip(&b) {
+                            total.merge(part);
+                        }
+                        a
+                    },
+                )
+        }
+        #[cfg(not(feature = "parallel"))]
+        self.count_triads_per_node()
+    }
+
+    /// Per-node triad participation; same as [`count_triads_per_node`](Self::count_triads_per_node)
+    #[deprecated(note = "use count_triads_per_node")]
+    pub fn node_triad_participation(&self) -> Vec<TriadCounts> {
+        self.count_triads_per_node()
+    }
+
+    /// Number of unstable triads each present edge belongs to, highest first
+    ///
+    /// Every present edge `(i, j)` with `i < j` is listed, including those in
+    /// no unstable triad; ties are ordered by `(i, j)`. The edges at the top
+    /// are the relationships whose change would resolve the most frustration.
+    /// Runs the full `O(n³)` triad loop and keeps an `n * n` tally.
+    pub fn edge_frustration_scores(&self) -> Vec<((usize, usize), u64)> {
+        let n = self.n;
+        let mut tally = vec![0u64; n * n];
+        self.for_each_triad(|i, j, k, triad_type| {
+            if !triad_type.is_stable() {
+                tally[i * n + j] += 1;
+                tally[i * n + k] += 1;
+                tally[j * n + k] += 1;
+            }
+        });
+
+        let mut scores: Vec<((usize, usize), u64)> = (0..n)
+            .flat_map(|i| ((i + 1)..n).map(move |j| (i, j)))
+            .filter(|&(i, j)| self.signs[i * n + j] != 0)
+            .map(|(i, j)| ((i, j), tally[i * n + j]))
+            .collect();
+        scores.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        scores
+    }
+
+    /// Per-node counts paired with node labels
+    pub fn count_triads_per_node_labeled(&self) -> Vec<(&str, TriadCounts)> {
+        self.labels
+            .iter()
+            .map(String::as_str)
+            .zip(self.count_triads_per_node())
+            .collect()
+    }
+
+    /// Write per-node triad participation as CSV, ordered by `by`
+    ///
+    /// Columns: `label,three_positive,two_positive,one_positive,zero_positive,stable,unstable`.
+    /// Count-based orderings are descending; ties keep the original node order.
+    pub fn output_node_report_sorted_csv<P: AsRef<Path>>(
+        &self,
+        path: P,
+        by: SortKey,
+    ) -> Result<(), TriadError> {
+        let per_node = self.count_triads_per_node();
+        let mut order: Vec<usize> = (0..self.n).collect();
+        match by {
+            SortKey::TotalTriads => order.sort_by_key(|&i| std::cmp::Reverse(per_node[i].total())),
+            SortKey::UnstableTriads => {
+                order.sort_by_key(|&i| std::cmp::Reverse(per_node[i].unstable()))
+            }
+            SortKey::Label => order.sort_by(|&a, &b| self.labels[a].cmp(&self.labels[b])),
+        }
+
+        self.write_node_report(path, &per_node, &order)
+    }
+
+    /// Write per-node triad participation as CSV in input node order
+    ///
+    /// Same columns as
+    /// [`output_node_report_sorted_csv`](Self::output_node_report_sorted_csv),
+    /// one row per node in the order of the input header, so the file lines
+    /// up with the input matrix.
+    pub fn output_per_node_csv<P: AsRef<Path>>(&self, path: P) -> Result<(), TriadError> {
+        let per_node = self.count_triads_per_node();
+        let order: Vec<usize> = (0..self.n).collect();
+        self.write_node_report(path, &per_node, &order)
+    }
+
+    /// Write the per-node CSV report with rows in `order`
+    fn write_node_report<P: AsRef<Path>>(
+        &self,
+        path: P,
+        per_node: &[TriadCounts],
+        order: &[usize],
+    ) -> Result<(), TriadError> {
+        let mut writer = csv::Writer::from_path(path)?;
+        writer.write_record([
+            "label",
+            "three_positive",
+            "two_positive",
+            "one_positive",
+            "zero_positive",
+            "stable",
+            "unstable",
+        ])?;
+        for &i in order {
+            let c = &per_node[i];
+            writer.write_record([
+                self.labels[i].clone(),
+                c.three_positive.to_string(),
+                c.two_positive.to_string(),
+                c.one_positive.to_string(),
+                c.zero_positive.to_string(),
+                c.stable().to_string(),
+                c.unstable().to_string(),
+            ])?;
+        }
+        writer.flush()?;
+
+        Ok(())
+    }
+
+    /// Write one CSV row per complete triad
+    ///
+    /// Columns are `node_a,node_b,node_c,positive_edges,stable`, with nodes
+    /// given by label in increasing index order. Rows are streamed from
+    /// [`enumerate_triads`](Self::enumerate_triads), so memory use does not
+    /// grow with the number of triads.
+    pub fn output_triads_csv<P: AsRef<Path>>(&self, path: P) -> Result<(), TriadError> {
+        let mut writer = csv::Writer::from_path(path)?;
+        writer.write_record(["node_a", "node_b", "node_c", "positive_edges", "stable"])?;
+        for (i, j, k, pos_count) in self.enumerate_triads() {
+            let stable = TriadType::from_positive_count(pos_count).is_stable();
+            writer.write_record([
+                self.labels[i].as_str(),
+                self.labels[j].as_str(),
+                self.labels[k].as_str(),
+                &pos_count.to_string(),
+                if stable { "true" } else { "false" },
+            ])?;
+        }
+        writer.flush()?;
+
+        Ok(())
+    }
+
+    /// Number of node triples, `C(n, 3)`; 0 for fewer than 3 nodes, and
+    /// `u64::MAX` if the count does not fit
+    pub fn possible_triads(&self) -> u64 {
+        choose3(self.n)
+    }
+
+    /// Fraction of possible triads that are complete: `total() / C(n, 3)`
+    ///
+    /// Uses the counts from the last `run()`. Returns 0.0 for fewer than 3 nodes.
+    pub fn triad_density(&self) -> f64 {
+        let possible = self.possible_triads();
+        if possible == 0 {
+            0.0
+        } else {
+            self.counts.total() as f64 / possible as f64
+        }
+    }
+
+    /// How connected the network is at the triangle level; another name for
+    /// [`triad_density`](Self::triad_density)
+    #[deprecated(note = "use triad_density")]
+    pub fn triad_completeness(&self) -> f64 {
+        self.triad_density()
+    }
+
+    /// Per-category densities `[three, two, one, zero positive] / C(n, 3)`
+    ///
+    /// Uses the counts from the last `run()`. All zero for fewer than 3 nodes.
+    pub fn triad_category_densities(&self) -> [f64; 4] {
+        let possible = self.possible_triads();
+        if possible == 0 {
+            return [0.0; 4];
+        }
+        let possible = possible as f64;
+        [
+            self.counts.three_positive as f64 / possible,
+            self.counts.two_positive as f64 / possible,
+            self.counts.one_positive as f64 / possible,
+            self.counts.zero_positive as f64 / possible,
+        ]
+    }
+
+    /// Triad profile: the four category proportions from the last `run()`
+    ///
+    /// Ordered `[three, two, one, zero positive]` and summing to 1, suitable as
+    /// a feature vector for clustering networks. All zero if no triads exist.
+    pub fn triad_profile_vector(&self) -> [f64; 4] {
+        self.counts.fractions()
+    }
+
+    /// Get the computed triad counts
+    pub fn counts(&self) -> &TriadCounts {
+        &self.counts
+    }
+
+    /// Get number of nodes
+    pub fn node_count(&self) -> usize {
+        self.n
+    }
+
+    /// Get node labels
+    pub fn labels(&self) -> &[String] {
+        &self.labels
+    }
+
+    /// Index of the node named `name`, if any
+    pub fn label_index(&self, name: &str) -> Option<usize> {
+        self.label_index.get(name).copied()
+    }
+
+    /// Free the weight matrix, keeping only the signs
+    ///
+    /// Counting reads only the one-byte-per-pair sign matrix, so this
+    /// reclaims the eight bytes per pair of weights once a network is loaded.
+    /// Afterwards [`adjacency`](Self::adjacency) is empty. Threshold and sign
+    /// options are stored for the next load without touching the current
+    /// signs, and [`is_symmetric`](Self::is_symmetric) compares signs. Other
+    /// methods that read weights (symmetry reports and symmetrization,
+    /// sweeps, sparsification, edge updates, and weighted sums) panic, or
+    /// fail with [`TriadError::WeightsFreed`] where they return a `Result`,
+    /// until another network is loaded.
+    pub fn free_adjacency(&mut self) {
+        self.adj.clear();
+        self.adj.shrink_to_fit();
+        self.original_adj = None;
+    }
+
+    /// Free the weight matrix; same as [`free_adjacency`](Self::free_adjacency)
+    #[deprecated(note = "use free_adjacency")]
+    pub fn into_signs_only(&mut self) {
+        self.free_adjacency();
+    }
+
+    /// Whether edge weights are available, i.e. not freed by
+    /// [`free_adjacency`](Self::free_adjacency)
+    pub fn has_adjacency(&self) -> bool {
+        self.adj.len() == self.n * self.n
+    }
+
+    /// Panic with a clear message if the weights were freed
+    fn require_weights(&self, method: &str) {
+        assert!(
+            self.has_adjacency(),
+            "{} needs edge weights, which were freed by free_adjacency",
+            method
+        );
+    }
+
+    /// Stored weights, row-major `n * n`: `adjacency()[i * n + j]` is the
+    /// weight from node `i` to node `j`
+    ///
+    /// The diagonal is zero unless loaded with
+    /// [`set_keep_diagonal`](Self::set_keep_diagonal).
+    pub fn adjacency(&self) -> &[f64] {
+        &self.adj
+    }
+
+    /// Create plugin from adjacency matrix directly (for testing/benchmarking)
+    ///
+    /// NaN and infinite values are treated as no edge, with a warning; use
+    /// [`try_from_matrix`](Self::try_from_matrix) to reject them instead.
+    pub fn from_matrix(matrix: Vec<Vec<f64>>) -> Self {
+        let n = matrix.len();
+        let mut adj = vec![0.0; n * n];
+
+        for (i, row) in matrix.iter().enumerate() {
+            for (j, &val) in row.iter().enumerate() {
+                if i != j {
+                    adj[i * n + j] = val;
+                }
+            }
+        }
+        resolve_non_finite(&mut adj, n, NonFinitePolicy::Zero, 0.0)
+            .expect("zeroing non-finite values cannot fail");
+
+        let signs = adj.iter().map(|&v| Self::to_sign(v)).collect();
+
+        let mut plugin = Self {
+            adj,
+            signs,
+            n,
+            labels: Vec::new(),
+            label_index: HashMap::new(),
+            counts: TriadCounts::default(),
+            counts_current: false,
+            original_adj: None,
+            config: Config::default(),
+        };
+        plugin.set_labels((0..n).map(|i| format!("Node{}", i)).collect());
+        plugin
+    }
+
+    /// Create plugin from a `petgraph` graph with `f64` edge weights
+    ///
+    /// Node indices become matrix indices `0..n` and nodes are named `Node0`,
+    /// `Node1`, ...; use [`from_petgraph_labeled`](Self::from_petgraph_labeled)
+    /// to take labels from the node weights. Undirected edges set both
+    /// directions; directed edges set only `adj[source][target]`, so the
+    /// symmetry options decide how they are read. With parallel edges the
+    /// last one wins. Self-loops are ignored, and NaN or infinite weights are
+    /// treated as no edge, as in [`from_matrix`](Self::from_matrix).
+    #[cfg(feature = "petgraph")]
+    pub fn from_petgraph<N, Ty, Ix>(graph: &petgraph::Graph<N, f64, Ty, Ix>) -> Self
+    where
+        Ty: petgraph::EdgeType,
+        Ix: petgraph::graph::IndexType,
+    {
+        let labels = (0..graph.node_count())
+            .map(|i| format!("Node{}", i))
+            .collect();
+        Self::from_petgraph_with_labels(graph, labels)
+    }
+
+    /// Like [`from_petgraph`](Self::from_petgraph), with node weights as labels
+    #[cfg(feature = "petgraph")]
+    pub fn from_petgraph_labeled<N, Ty, Ix>(graph: &petgraph::Graph<N, f64, Ty, Ix>) -> Self
+    where
+        N: AsRef<str>,
+        Ty: petgraph::EdgeType,
+        Ix: petgraph::graph::IndexType,
+    {
+        let labels = graph
+            .node_weights()
+            .map(|w| w.as_ref().to_string())
+            .collect();
+        Self::from_petgraph_with_labels(graph, labels)
+    }
+
+    #[cfg(feature = "petgraph")]
+    fn from_petgraph_with_labels<N, Ty, Ix>(
+        graph: &petgraph::Graph<N, f64, Ty, Ix>,
+        labels: Vec<String>,
+    ) -> Self
+    where
+        Ty: petgraph::EdgeType,
+        Ix: petgraph::graph::IndexType,
+    {
+        use petgraph::visit::EdgeRef;
+
+        let n = graph.node_count();
+        let mut adj = vec![0.0; n * n];
+        for edge in graph.edge_references() {
+            let (u, v) = (edge.source().index(), edge.target().index());
+            if u == v {
+                continue;
+            }
+            adj[u * n + v] = *edge.weight();
+            if !graph.is_directed() {
+                adj[v * n + u] = *edge.weight();
+            }
+        }
+        resolve_non_finite(&mut adj, n, NonFinitePolicy::Zero, 0.0)
+            .expect("zeroing non-finite values cannot fail");
+
+        let mut plugin = Self::new();
+        plugin.adj = adj;
+        plugin.n = n;
+        plugin.set_labels(labels);
+        plugin.compute_signs();
+        plugin
+    }
+
+    /// Create plugin from adjacency matrix, rejecting NaN and infinite values
+    ///
+    /// Fails with [`TriadError::NonFiniteValue`] at the first non-finite
+    /// off-diagonal cell; otherwise identical to [`from_matrix`](Self::from_matrix).
+    pub fn try_from_matrix(matrix: Vec<Vec<f64>>) -> Result<Self, TriadError> {
+        for (row, values) in matrix.iter().enumerate() {
+            for (col, &value) in values.iter().enumerate() {
+                if row != col && !value.is_finite() {
+                    return Err(TriadError::NonFiniteValue { row, col, value });
+                }
+            }
+        }
+        Ok(Self::from_matrix(matrix))
+    }
+
+    /// Create plugin from a contiguous row-major `n * n` buffer
+    ///
+    /// Copies `data` once, zeroes the diagonal, and computes signs. Fails with
+    /// [`TriadError::DimensionMismatch`] unless `data.len() == n * n`, and with
+    /// [`TriadError::NonFiniteValue`] if an off-diagonal value is NaN or infinite.
+    pub fn from_flat(data: &[f64], n: usize) -> Result<Self, TriadError> {
+        if data.len() != n * n {
+            return Err(TriadError::DimensionMismatch {
+                expected: n * n,
+                found: data.len(),
+            });
+        }
+
+        let mut plugin = Self::new();
+        plugin.adj = data.to_vec();
+        resolve_non_finite(&mut plugin.adj, n, NonFinitePolicy::Error, 0.0)?;
+        plugin.n = n;
+        plugin.set_labels((0..n).map(|i| format!("Node{}", i)).collect());
+        for i in 0..n {
+            plugin.adj[i * n + i] = 0.0;
+        }
+        plugin.compute_signs();
+
+        Ok(plugin)
+    }
+
+    /// Create a reproducible random signed network
+    ///
+    /// Each unordered pair gets an edge with probability `density`, and each
+    /// edge is +1 with probability `positive_prob` and -1 otherwise. The same
+    /// `seed` always yields the same network, on every platform.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either probability lies outside `[0, 1]`.
+    pub fn random(n: usize, positive_prob: f64, density: f64, seed: u64) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&positive_prob),
+            "positive_prob {} is not a probability",
+            positive_prob
+        );
+        assert!(
+            (0.0..=1.0).contains(&density),
+            "density {} is not a probability",
+            density
+        );
+
+        let mut rng = SplitMix64(seed);
+        let mut adj = vec![0.0; n * n];
+        for i in 0..n {
+            for j in (i + 1)..n {
+                if rng.next_f64() < density {
+                    let w = if rng.next_f64() < positive_prob {
+                        1.0
+                    } else {
+                        -1.0
+                    };
+                    adj[i * n + j] = w;
+                    adj[j * n + i] = w;
+                }
+            }
+        }
+
+        Self::from_flat(&adj, n).expect("generated matrix is n * n and finite")
+    }
+
+    /// Create plugin by projecting signed hyperedges onto pairwise ties
+    ///
+    /// Each hyperedge `(members, sign)` assigns `sign` to every pair of distinct
+    /// members. When a pair shares several hyperedges, their signs are summed and
+    /// the pair's edge takes the sign of the total: a majority of cooperative
+    /// groups makes it positive, a majority of conflictual groups makes it
+    /// negative, and an exact tie cancels to no edge. Hyperedges with sign 0
+    /// contribute nothing.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any member index is `>= n`.
+    pub fn from_hyperedges(hyperedges: &[(Vec<usize>, i8)], n: usize) -> Self {
+        let mut adj = vec![0.0; n * n];
+
+        for (members, sign) in hyperedges {
+            let vote = Self::to_sign(*sign as f64) as f64;
+            for (a, &u) in members.iter().enumerate() {
+                assert!(u < n, "hyperedge member {} out of range for {} nodes", u, n);
+                for &v in &members[a + 1..] {
+                    if u != v {
+                        adj[u * n + v] += vote;
+                        adj[v * n + u] += vote;
+                    }
+                }
+            }
+        }
+
+        let signs = adj.iter().map(|&v| Self::to_sign(v)).collect();
+
+        let mut plugin = Self {
+            adj,
+            signs,
+            n,
+            labels: Vec::new(),
+            label_index: HashMap::new(),
+            counts: TriadCounts::default(),
+            counts_current: false,
+            original_adj: None,
+            config: Config::default(),
+        };
+        plugin.set_labels((0..n).map(|i| format!("Node{}", i)).collect());
+        plugin
+    }
+}
+
+impl Default for TriadCounterPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    fn create_test_csv(content: &str) -> NamedTempFile {
+        let mut file = NamedTempFile::new().unwrap();
+        write!(file, "{}", content).unwrap();
+        file
+    }
+
+    #[test]
+    fn test_all_positive_triad() {
+        // 3 nodes, all positive edges -> 1 stable triad (3 positive)
+        let matrix = vec![
+            vec![0.0, 1.0, 1.0],
+            vec![1.0, 0.0, 1.0],
+            vec![1.0, 1.0, 0.0],
+        ];
+        let mut plugin = TriadCounterPlugin::from_matrix(matrix);
+        plugin.run();
+
+        assert_eq!(plugin.counts().three_positive, 1);
+        assert_eq!(plugin.counts().stable(), 1);
+        assert_eq!(plugin.counts().unstable(), 0);
+    }
+
+    #[test]
     fn test_all_negative_triad() {
         // 3 nodes, all negative edges -> 1 unstable triad (0 positive)
         let matrix = vec![
-            vec![0.0, -1.0, -1.0],
+            vec![0.0, -1.0, -1.0],
+            vec![-1.0, 0.0, -1.0],
+            vec![-1.0, -1.0, 0.0],
+        ];
+        let mut plugin = TriadCounterPlugin::from_matrix(matrix);
+        plugin.run();
+
+        assert_eq!(plugin.counts().zero_positive, 1);
+        assert_eq!(plugin.counts().stable(), 0);
+        assert_eq!(plugin.counts().unstable(), 1);
+    }
+
+    #[test]
+    fn test_one_positive_triad() {
+        // 3 nodes, 1 positive 2 negative -> 1 stable triad
+        let matrix = vec![
+            vec![0.0, 1.0, -1.0],
+            vec![1.0, 0.0, -1.0],
+            vec![-1.0, -1.0, 0.0],
+        ];
+        let mut plugin = TriadCounterPlugin::from_matrix(matrix);
+        plugin.run();
+
+        assert_eq!(plugin.counts().one_positive, 1);
+        assert_eq!(plugin.counts().stable(), 1);
+    }
+
+    #[test]
+    fn test_two_positive_triad() {
+        // 3 nodes, 2 positive 1 negative -> 1 unstable triad
+        let matrix = vec![
+            vec![0.0, 1.0, 1.0],
+            vec![1.0, 0.0, -1.0],
+            vec![1.0, -1.0, 0.0],
+        ];
+        let mut plugin = TriadCounterPlugin::from_matrix(matrix);
+        plugin.run();
+
+        assert_eq!(plugin.counts().two_positive, 1);
+        assert_eq!(plugin.counts().unstable(), 1);
+    }
+
+    #[test]
+    fn test_run_returns_counts() {
+        let mut plugin = TriadCounterPlugin::from_matrix(vec![
+            vec![0.0, 1.0, 1.0],
+            vec![1.0, 0.0, 1.0],
+            vec![1.0, 1.0, 0.0],
+        ]);
+        let counts = plugin.run().clone();
+
+        assert_eq!(counts.three_positive, 1);
+        assert_eq!(&counts, plugin.counts());
+    }
+
+    #[test]
+    fn test_balance_ratio() {
+        let counts = TriadCounts {
+            three_positive: 2,
+            two_positive: 1,
+            one_positive: 1,
+            zero_positive: 0,
+        };
+        assert_eq!(counts.balance_ratio(), Some(0.75));
+        assert_eq!(counts.frustration_ratio(), Some(0.25));
+
+        let empty = TriadCounts::default();
+        assert_eq!(empty.balance_ratio(), None);
+        assert_eq!(empty.frustration_ratio(), None);
+    }
+
+    #[test]
+    fn test_csv_parsing() {
+        let csv = "\"\",A,B,C\nA,0,1,-1\nB,1,0,1\nC,-1,1,0";
+        let file = create_test_csv(csv);
+
+        let mut plugin = TriadCounterPlugin::new();
+        plugin.input(file.path()).unwrap();
+
+        assert_eq!(plugin.node_count(), 3);
+        assert_eq!(plugin.labels(), &["A", "B", "C"]);
+    }
+
+    #[test]
+    fn test_dimacs_parsing() {
+        let dimacs =
+            "c signed triangle plus pendant\np edge 4 4\ne 1 2 1\ne 2 3 -1\ne 1 3 -1\ne 3 4 1\n";
+        let file = create_test_csv(dimacs);
+
+        let mut plugin = TriadCounterPlugin::new();
+        plugin.input_dimacs(file.path()).unwrap();
+        plugin.run();
+
+        assert_eq!(plugin.node_count(), 4);
+        assert_eq!(plugin.labels()[3], "Node3");
+        assert_eq!(plugin.counts().one_positive, 1);
+        assert_eq!(plugin.counts().total(), 1);
+
+        let short = create_test_csv("p edge 3 3\ne 1 2 1\ne 2 3 1\n");
+        assert!(TriadCounterPlugin::new()
+            .input_dimacs(short.path())
+            .is_err());
+    }
+
+    #[test]
+    fn test_dimacs_failed_load_clears() {
+        let good = create_test_csv("p edge 3 3\ne 1 2 1\ne 2 3 1\ne 1 3 1\n");
+        let mut plugin = TriadCounterPlugin::new();
+        plugin.input_dimacs(good.path()).unwrap();
+        assert_eq!(plugin.run().three_positive, 1);
+
+        // The problem line parses before each error, declaring a larger network
+        for bad in [
+            "p edge 10 2\ne 1 2 1\n",
+            "p edge 10 2\ne 1 2 1\ne 2 x 1\n",
+            "p col 3 1\ne 1 2 1\n",
+            // Would need far more memory than exists, or overflow n * n
+            "p edge 4000000000 1\ne 1 2 1\n",
+            "p edge 18446744073709551615 1\ne 1 2 1\n",
+        ] {
+            plugin.input_dimacs(good.path()).unwrap();
+            let bad = create_test_csv(bad);
+            assert!(plugin.input_dimacs(bad.path()).is_err());
+            assert_eq!(plugin.node_count(), 0);
+            assert!(plugin.labels().is_empty());
+            assert_eq!(plugin.counts(), &TriadCounts::default());
+            assert_eq!(plugin.count_triads_optimized(), TriadCounts::default());
+        }
+
+        let huge = create_test_csv("c oversized\np edge 4000000000 1\ne 1 2 1\n");
+        assert!(matches!(
+            plugin.input_dimacs(huge.path()),
+            Err(TriadError::Malformed { line: 2, .. })
+        ));
+
+        // A reload resets counts from the previous network
+        plugin.input_dimacs(good.path()).unwrap();
+        plugin.run();
+        let pendant = create_test_csv("p edge 3 1\ne 1 2 1\n");
+        plugin.input_dimacs(pendant.path()).unwrap();
+        assert_eq!(plugin.counts(), &TriadCounts::default());
+        assert_eq!(plugin.run().total(), 0);
+    }
+
+    #[test]
+    fn test_dimacs_non_finite_policy() {
+        let file = create_test_csv(
+            "p edge 3 3
+e 1 2 1
+e 2 3 inf
+e 1 3 NaN
+",
+        );
+
+        let mut plugin = TriadCounterPlugin::new();
+        let err = plugin.input_dimacs(file.path()).unwrap_err();
+        assert!(matches!(
+            err,
+            TriadError::NonFiniteValue { row: 0, col: 2, value } if value.is_nan()
+        ));
+        assert_eq!(plugin.node_count(), 0);
+
+        plugin.set_non_finite_policy(NonFinitePolicy::Zero);
+        plugin.input_dimacs(file.path()).unwrap();
+        assert_eq!(plugin.node_count(), 3);
+        assert_eq!(plugin.run().total(), 0);
+    }
+
+    #[test]
+    fn test_error_variants() {
+        let mut plugin = TriadCounterPlugin::new();
+
+        let err = plugin.input("/nonexistent/network.csv").unwrap_err();
+        assert!(matches!(err, TriadError::Io(_)));
+
+        let empty = create_test_csv("\"\"\n");
+        let err = plugin.input(empty.path()).unwrap_err();
+        assert!(matches!(err, TriadError::EmptyMatrix));
+
+        let bad = create_test_csv("p edge 2 1\ne 1 x 1\n");
+        let err = plugin.input_dimacs(bad.path()).unwrap_err();
+        assert!(matches!(err, TriadError::Malformed { line: 2, .. }));
+
+        let err = plugin.output("/nonexistent/dir/out.txt").unwrap_err();
+        assert!(matches!(err, TriadError::Io(_)));
+        assert!(std::error::Error::source(&err).is_some());
+    }
+
+    #[test]
+    fn test_non_square_matrix() {
+        // 4 labels but only 3 data rows
+        let truncated = create_test_csv("\"\",A,B,C,D\nA,0,1,1,1\nB,1,0,1,1\nC,1,1,0,1");
+        let err = TriadCounterPlugin::new()
+            .input(truncated.path())
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            TriadError::NonSquareMatrix { rows: 3, cols: 4 }
+        ));
+
+        let extra = create_test_csv("\"\",A,B\nA,0,1\nB,1,0\nC,1,1");
+        let err = TriadCounterPlugin::new().input(extra.path()).unwrap_err();
+        assert!(matches!(
+            err,
+            TriadError::NonSquareMatrix { rows: 3, cols: 2 }
+        ));
+    }
+
+    #[test]
+    fn test_edgelist_reload() {
+        let triangle = create_test_csv("A,B,1\nB,C,1\nC,A,1");
+        let mut plugin = TriadCounterPlugin::new();
+        plugin.input_edgelist(triangle.path()).unwrap();
+        assert_eq!(plugin.run().three_positive, 1);
+
+        // Counts from the previous network do not survive a reload
+        let path = create_test_csv("A,B,1\nB,C,-1");
+        plugin.input_edgelist(path.path()).unwrap();
+        assert_eq!(plugin.counts(), &TriadCounts::default());
+
+        // Non-finite weights fail as in a CSV matrix, leaving the plugin empty
+        let nan = create_test_csv("A,B,1\nB,C,NaN\nC,A,1");
+        assert!(matches!(
+            plugin.input_edgelist(nan.path()),
+            Err(TriadError::NonFiniteValue { row: 1, col: 2, .. })
+        ));
+        assert_eq!(plugin.node_count(), 0);
+
+        plugin.set_non_finite_policy(NonFinitePolicy::Zero);
+        plugin.input_edgelist(nan.path()).unwrap();
+        assert_eq!(plugin.pair_sign(1, 2), 0);
+        assert_eq!(plugin.run().total(), 0);
+    }
+
+    #[test]
+    fn test_edgelist_parsing() {
+        // Header, a reversed duplicate (last wins), and an implied reverse edge
+        let edges = "source,target,sign\nA,B,1\nB,C,1\nC,A,-1\nB,A,-1\nC,D,1";
+        let file = create_test_csv(edges);
+
+        let mut plugin = TriadCounterPlugin::new();
+        plugin.input_edgelist(file.path()).unwrap();
+        plugin.run();
+
+        assert_eq!(plugin.labels(), &["A", "B", "C", "D"]);
+        assert_eq!(plugin.pair_sign(0, 1), -1);
+        assert_eq!(plugin.pair_sign(1, 2), 1);
+        // {A,B,C}: -, -, + -> one positive
+        assert_eq!(plugin.counts().one_positive, 1);
+        assert_eq!(plugin.counts().total(), 1);
+    }
+
+    #[test]
+    fn test_output_json() {
+        let csv = "\"\",A,\"B \"\"x\"\"\",C\nA,0,1,-1\nB,1,0,-1\nC,-1,-1,0";
+        let file = create_test_csv(csv);
+        let mut plugin = TriadCounterPlugin::new();
+        plugin.input(file.path()).unwrap();
+        plugin.run();
+
+        let out = NamedTempFile::new().unwrap();
+        plugin.output_json(out.path()).unwrap();
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(out.path()).unwrap()).unwrap();
+
+        assert_eq!(json["node_count"], 3);
+        assert_eq!(json["labels"][1], "B \"x\"");
+        assert_eq!(json["counts"]["one_positive"], 1);
+        assert_eq!(json["counts"]["stable"], 1);
+        assert_eq!(json["counts"]["unstable"], 0);
+    }
+
+    #[test]
+    fn test_output_run_report() {
+        let file = create_test_csv("\"\",A,B,C\nA,0,1,-1\nB,1,0,-1\nC,-1,-1,0");
+        let mut plugin = TriadCounterPlugin::new();
+        plugin.input(file.path()).unwrap();
+        plugin.run();
+
+        let out = NamedTempFile::new().unwrap();
+        plugin
+            .output_run_report(out.path(), "data\\net \"v2\".csv", 1.5)
+            .unwrap();
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(out.path()).unwrap()).unwrap();
+
+        assert_eq!(json["input"], "data\\net \"v2\".csv");
+        assert_eq!(json["node_count"], 3);
+        assert_eq!(json["elapsed_seconds"], 1.5);
+        assert_eq!(json["strategy"], plugin.counting_strategy().name());
+        assert_eq!(json["counts"]["three_positive"], 0);
+        assert_eq!(json["counts"]["one_positive"], 1);
+        assert_eq!(json["counts"].as_object().unwrap().len(), 6);
+        assert_eq!(json["counts"]["unstable"], 0);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serialize_counts() {
+        let counts = TriadCounts {
+            three_positive: 4,
+            two_positive: 3,
+            one_positive: 2,
+            zero_positive: 1,
+        };
+        let json = serde_json::to_value(&counts).unwrap();
+        assert_eq!(json["stable"], 6);
+        assert_eq!(json["unstable"], 4);
+        assert_eq!(json["zero_positive"], 1);
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn test_gzip_input() {
+        use flate2::write::GzEncoder;
+        use flate2::Compression;
+
+        let csv = "\"\",A,B,C\nA,0,1,-1\nB,1,0,-1\nC,-1,-1,0";
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(csv.as_bytes()).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        // Detected by extension
+        let mut file = tempfile::Builder::new()
+            .suffix(".csv.gz")
+            .tempfile()
+            .unwrap();
+        file.write_all(&compressed).unwrap();
+        let mut plugin = TriadCounterPlugin::new();
+        plugin.input(file.path()).unwrap();
+        assert_eq!(plugin.labels(), &["A", "B", "C"]);
+        assert_eq!(plugin.run().one_positive, 1);
+
+        // Detected by magic bytes
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(&compressed).unwrap();
+        let mut plugin = TriadCounterPlugin::new();
+        plugin.input(file.path()).unwrap();
+        assert_eq!(plugin.node_count(), 3);
+    }
+
+    #[test]
+    fn test_from_flat() {
+        let data = [5.0, 1.0, -1.0, 1.0, 5.0, -1.0, -1.0, -1.0, 5.0];
+        let mut plugin = TriadCounterPlugin::from_flat(&data, 3).unwrap();
+        assert_eq!(plugin.labels(), &["Node0", "Node1", "Node2"]);
+        assert_eq!(plugin.adj[4], 0.0);
+        assert_eq!(plugin.run().one_positive, 1);
+
+        let err = TriadCounterPlugin::from_flat(&data, 4).err().unwrap();
+        assert!(matches!(
+            err,
+            TriadError::DimensionMismatch {
+                expected: 16,
+                found: 9
+            }
+        ));
+    }
+
+    #[test]
+    fn test_larger_network() {
+        // 4 nodes = C(4,3) = 4 possible triads
+        let matrix = vec![
+            vec![0.0, 1.0, 1.0, 1.0],
+            vec![1.0, 0.0, 1.0, 1.0],
+            vec![1.0, 1.0, 0.0, 1.0],
+            vec![1.0, 1.0, 1.0, 0.0],
+        ];
+        let mut plugin = TriadCounterPlugin::from_matrix(matrix);
+        plugin.run();
+
+        // All positive edges -> all triads have 3 positive
+        assert_eq!(plugin.counts().three_positive, 4);
+        assert_eq!(plugin.counts().total(), 4);
+    }
+
+    #[test]
+    fn test_sequential_vs_parallel() {
+        // Create a moderate network
+        let n = 20;
+        let mut matrix = vec![vec![0.0; n]; n];
+        for (i, row) in matrix.iter_mut().enumerate() {
+            for (j, cell) in row.iter_mut().enumerate() {
+                if i != j {
+                    *cell = if (i + j) % 3 == 0 { -1.0 } else { 1.0 };
+                }
+            }
+        }
+
+        let plugin = TriadCounterPlugin::from_matrix(matrix);
+        let seq = plugin.count_triads_sequential();
+        let par = plugin.count_triads_parallel_chunked();
+
+        assert_eq!(seq, par);
+    }
+
+    #[test]
+    fn test_for_each_triad() {
+        let matrix = vec![
+            vec![0.0, 1.0, 1.0, -1.0],
+            vec![1.0, 0.0, -1.0, 0.0],
+            vec![1.0, -1.0, 0.0, -1.0],
+            vec![-1.0, 0.0, -1.0, 0.0],
+        ];
+        let plugin = TriadCounterPlugin::from_matrix(matrix);
+
+        let mut seen = Vec::new();
+        plugin.for_each_triad(|i, j, k, t| seen.push((i, j, k, t)));
+
+        assert_eq!(
+            seen,
+            vec![
+                (0, 1, 2, TriadType::TwoPositive),
+                (0, 2, 3, TriadType::OnePositive),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_from_hyperedges() {
+        // Cooperative group {0,1,2}, conflictual groups {2,3} and {1,3}
+        let hyperedges = vec![(vec![0, 1, 2], 1), (vec![2, 3], -1), (vec![1, 3], -1)];
+        let mut plugin = TriadCounterPlugin::from_hyperedges(&hyperedges, 4);
+        plugin.run();
+
+        // {0,1,2} all positive, {1,2,3} one positive
+        assert_eq!(plugin.counts().three_positive, 1);
+        assert_eq!(plugin.counts().one_positive, 1);
+        assert_eq!(plugin.counts().total(), 2);
+
+        // Opposing groups on the same pair cancel out
+        let hyperedges = vec![(vec![0, 1, 2], 1), (vec![0, 1], -1)];
+        let mut plugin = TriadCounterPlugin::from_hyperedges(&hyperedges, 3);
+        plugin.run();
+        assert_eq!(plugin.counts().total(), 0);
+    }
+
+    #[test]
+    fn test_unbalanced_4cycles() {
+        // Square 0-1-2-3-0 without chords
+        let square = |s01: f64, s12: f64, s23: f64, s30: f64| {
+            vec![
+                vec![0.0, s01, 0.0, s30],
+                vec![s01, 0.0, s12, 0.0],
+                vec![0.0, s12, 0.0, s23],
+                vec![s30, 0.0, s23, 0.0],
+            ]
+        };
+
+        let plugin = TriadCounterPlugin::from_matrix(square(1.0, 1.0, 1.0, -1.0));
+        assert_eq!(plugin.count_unbalanced_4cycles(), 1);
+        assert!(plugin.has_unbalanced_4cycle());
+
+        // Triad-free, but balanced with an even number of negatives
+        let plugin = TriadCounterPlugin::from_matrix(square(1.0, -1.0, 1.0, -1.0));
+        assert_eq!(plugin.count_unbalanced_4cycles(), 0);
+        assert!(!plugin.has_unbalanced_4cycle());
+
+        // K4 with a single negative edge: it lies on 2 of the 3 four-cycles
+        let matrix = vec![
+            vec![0.0, -1.0, 1.0, 1.0],
+            vec![-1.0, 0.0, 1.0, 1.0],
+            vec![1.0, 1.0, 0.0, 1.0],
+            vec![1.0, 1.0, 1.0, 0.0],
+        ];
+        let plugin = TriadCounterPlugin::from_matrix(matrix);
+        assert_eq!(plugin.count_unbalanced_4cycles(), 2);
+    }
+
+    #[test]
+    fn test_node_report_sorted_csv() {
+        let csv = "\"\",C,A,B,D\nC,0,1,1,0\nA,1,0,-1,-1\nB,1,-1,0,-1\nD,0,-1,-1,0";
+        let file = create_test_csv(csv);
+        let mut plugin = TriadCounterPlugin::new();
+        plugin.input(file.path()).unwrap();
+
+        let per_node = plugin.count_triads_per_node();
+        let sum: u64 = per_node.iter().map(|c| c.total()).sum();
+        assert_eq!(sum, 2 * 3);
+
+        let out = NamedTempFile::new().unwrap();
+        plugin
+            .output_node_report_sorted_csv(out.path(), SortKey::UnstableTriads)
+            .unwrap();
+        let written = std::fs::read_to_string(out.path()).unwrap();
+        let first_col: Vec<&str> = written
+            .lines()
+            .map(|l| l.split(',').next().unwrap())
+            .collect();
+        assert_eq!(first_col, vec!["label", "A", "B", "C", "D"]);
+
+        plugin
+            .output_node_report_sorted_csv(out.path(), SortKey::Label)
+            .unwrap();
+        let written = std::fs::read_to_string(out.path()).unwrap();
+        assert!(written.lines().nth(1).unwrap().starts_with("A,"));
+        assert!(written.lines().nth(3).unwrap().starts_with("C,0,1,0,0,0,1"));
+
+        plugin.output_per_node_csv(out.path()).unwrap();
+        let written = std::fs::read_to_string(out.path()).unwrap();
+        let first_col: Vec<&str> = written
+            .lines()
+            .map(|l| l.split(',').next().unwrap())
+            .collect();
+        assert_eq!(first_col, vec!["label", "C", "A", "B", "D"]);
+        assert_eq!(written.lines().nth(1).unwrap(), "C,0,1,0,0,0,1");
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_triad_density() {
+        // 4 nodes, C(4,3) = 4 possible; only {0,1,2} is complete
+        let matrix = vec![
+            vec![0.0, 1.0, -1.0, 0.0],
+            vec![1.0, 0.0, -1.0, 0.0],
+            vec![-1.0, -1.0, 0.0, 1.0],
+            vec![0.0, 0.0, 1.0, 0.0],
+        ];
+        let mut plugin = TriadCounterPlugin::from_matrix(matrix);
+        plugin.run();
+
+        assert_eq!(plugin.possible_triads(), 4);
+        assert_eq!(plugin.triad_density(), 0.25);
+        assert_eq!(plugin.triad_completeness(), 0.25);
+        assert_eq!(plugin.triad_category_densities(), [0.0, 0.0, 0.25, 0.0]);
+
+        let tiny = TriadCounterPlugin::from_matrix(vec![vec![0.0, 1.0], vec![1.0, 0.0]]);
+        assert_eq!(tiny.possible_triads(), 0);
+        assert_eq!(tiny.triad_density(), 0.0);
+        assert_eq!(tiny.triad_completeness(), 0.0);
+    }
+
+    #[test]
+    fn test_positive_fraction() {
+        let matrix = vec![
+            vec![0.0, 1.0, -1.0, 1.0],
+            vec![1.0, 0.0, 1.0, 0.0],
+            vec![-1.0, 1.0, 0.0, 0.0],
+            vec![1.0, 0.0, 0.0, 0.0],
+        ];
+        let plugin = TriadCounterPlugin::from_matrix(matrix);
+        assert_eq!(plugin.positive_fraction(), 0.75);
+        assert!(!plugin.is_sign_skewed());
+
+        let all_positive = TriadCounterPlugin::from_matrix(vec![
+            vec![0.0, 1.0, 1.0],
+            vec![1.0, 0.0, 1.0],
+            vec![1.0, 1.0, 0.0],
+        ]);
+        assert_eq!(all_positive.positive_fraction(), 1.0);
+        assert!(all_positive.is_sign_skewed());
+
+        assert!(TriadCounterPlugin::new().positive_fraction().is_nan());
+    }
+
+    #[test]
+    fn test_sparsify_topk() {
+        // Node 3 is weakly tied to everyone; with k = 2 its edges only survive
+        // where it is among the neighbor's two strongest
+        let matrix = vec![
+            vec![0.0, 5.0, 4.0, 0.1],
+            vec![5.0, 0.0, -3.0, 0.2],
+            vec![4.0, -3.0, 0.0, -0.3],
+            vec![0.1, 0.2, -0.3, 0.0],
+        ];
+        let plugin = TriadCounterPlugin::from_matrix(matrix);
+        assert_eq!(plugin.count_triads_optimized().total(), 4);
+
+        let mut sparse = plugin.sparsify_topk(1);
+        sparse.run();
+        // Top-1: 0->1, 1->0, 2->0, 3->2; union keeps 0-1, 0-2, 2-3
+        assert_eq!(sparse.counts().total(), 0);
+
+        let mut sparse = plugin.sparsify_topk(2);
+        sparse.run();
+        // Adds 1-2 (from 1 and 2) and 1-3 (from 3), leaving 0-3 dropped
+        assert_eq!(sparse.counts().total(), 2);
+        assert_eq!(sparse.labels(), plugin.labels());
+    }
+
+    #[test]
+    fn test_list_triads_order() {
+        let n = 12;
+        let mut matrix = vec![vec![0.0; n]; n];
+        for (i, row) in matrix.iter_mut().enumerate() {
+            for (j, cell) in row.iter_mut().enumerate() {
+                if i != j {
+                    *cell = ((i * 7 + j * 7) % 5) as f64 - 2.0;
+                }
+            }
+        }
+        let plugin = TriadCounterPlugin::from_matrix(matrix);
+
+        let triads = plugin.list_triads();
+        assert_eq!(
+            triads.len() as u64,
+            plugin.count_triads_sequential().total()
+        );
+        assert!(triads
+            .windows(2)
+            .all(|w| (w[0].0, w[0].1, w[0].2) < (w[1].0, w[1].1, w[1].2)));
+
+        let unstable = plugin.list_triads_of_type(TriadType::ZeroPositive);
+        let expected: Vec<_> = triads
+            .iter()
+            .filter(|t| t.3 == TriadType::ZeroPositive)
+            .map(|&(i, j, k, _)| (i, j, k))
+            .collect();
+        assert_eq!(unstable, expected);
+    }
+
+    #[test]
+    fn test_triad_type_helpers() {
+        let all = [
+            TriadType::ThreePositive,
+            TriadType::TwoPositive,
+            TriadType::OnePositive,
+            TriadType::ZeroPositive,
+        ];
+        for t in all {
+            assert_eq!(TriadType::from_positive_count(t.positive_edges()), t);
+            assert_eq!(t.is_stable(), t.positive_edges() % 2 == 1);
+        }
+
+        // Every listed triad agrees with the aggregate classification
+        let matrix = vec![
+            vec![0.0, 1.0, 1.0, -1.0],
+            vec![1.0, 0.0, -1.0, -1.0],
+            vec![1.0, -1.0, 0.0, -1.0],
+            vec![-1.0, -1.0, -1.0, 0.0],
+        ];
+        let plugin = TriadCounterPlugin::from_matrix(matrix);
+        let counts = plugin.count_triads_sequential();
+        let stable = plugin
+            .list_triads()
+            .iter()
+            .filter(|(_, _, _, t)| t.is_stable())
+            .count() as u64;
+        assert_eq!(stable, counts.stable());
+    }
+
+    #[test]
+    fn test_random_network() {
+        let a = TriadCounterPlugin::random(200, 0.7, 0.3, 42);
+        let b = TriadCounterPlugin::random(200, 0.7, 0.3, 42);
+        let c = TriadCounterPlugin::random(200, 0.7, 0.3, 43);
+        assert_eq!(a.count_triads_sequential(), b.count_triads_sequential());
+        assert_ne!(a.count_triads_sequential(), c.count_triads_sequential());
+
+        // 19900 pairs: sample proportions land close to the parameters
+        let summary = a.network_summary();
+        assert!((summary.density - 0.3).abs() < 0.02);
+        assert!((a.positive_fraction() - 0.7).abs() < 0.03);
+
+        assert_eq!(
+            TriadCounterPlugin::random(20, 0.5, 0.0, 1)
+                .network_summary()
+                .edges(),
+            0
+        );
+        let full = TriadCounterPlugin::random(20, 1.0, 1.0, 1);
+        assert_eq!(full.count_triads_sequential().three_positive, 1140);
+    }
+
+    #[test]
+    fn test_structural_balance() {
+        // Two factions {0, 1} and {2, 3}: friends inside, enemies across
+        let matrix = vec![
+            vec![0.0, 1.0, -1.0, -1.0, 0.0],
+            vec![1.0, 0.0, -1.0, -1.0, 0.0],
+            vec![-1.0, -1.0, 0.0, 1.0, 0.0],
+            vec![-1.0, -1.0, 1.0, 0.0, 0.0],
+            vec![0.0, 0.0, 0.0, 0.0, 0.0],
+        ];
+        let plugin = TriadCounterPlugin::from_matrix(matrix);
+        assert!(plugin.is_balanced());
+        assert_eq!(
+            plugin.structural_balance_partition(),
+            Some(vec![0, 0, 1, 1, 0])
+        );
+
+        // An all-negative triangle cannot be split in two
+        let matrix = vec![
+            vec![0.0, -1.0, -1.0],
+            vec![-1.0, 0.0, -1.0],
+            vec![-1.0, -1.0, 0.0],
+        ];
+        let plugin = TriadCounterPlugin::from_matrix(matrix);
+        assert!(!plugin.is_balanced());
+        assert_eq!(plugin.structural_balance_partition(), None);
+
+        // A chordless 4-cycle with one negative edge has no triangles at all,
+        // yet is unbalanced
+        let matrix = vec![
+            vec![0.0, 1.0, 0.0, -1.0],
+            vec![1.0, 0.0, 1.0, 0.0],
+            vec![0.0, 1.0, 0.0, 1.0],
+            vec![-1.0, 0.0, 1.0, 0.0],
+        ];
+        let plugin = TriadCounterPlugin::from_matrix(matrix);
+        assert!(plugin.is_balanced());
+        assert_eq!(plugin.structural_balance_partition(), None);
+    }
+
+    #[test]
+    fn test_wedges_and_closed_triads() {
+        // Triangle 0-1-2 (mixed signs) plus a pendant edge 2-3
+        let matrix = vec![
+            vec![0.0, 1.0, -1.0, 0.0],
+            vec![1.0, 0.0, -1.0, 0.0],
+            vec![-1.0, -1.0, 0.0, 1.0],
+            vec![0.0, 0.0, 1.0, 0.0],
+        ];
+        let plugin = TriadCounterPlugin::from_matrix(matrix);
+
+        // Degrees 2, 2, 3, 1 -> 1 + 1 + 3 + 0 wedges
+        assert_eq!(plugin.wedge_count(), 5);
+        assert_eq!(plugin.closed_triad_count(), 1);
+        let open = plugin.wedge_count() - 3 * plugin.closed_triad_count();
+        assert_eq!(open, 2);
+    }
+
+    #[test]
+    fn test_upper_triangular_storage() {
+        let n = 40;
+        let mut matrix = vec![vec![0.0; n]; n];
+        for (i, row) in matrix.iter_mut().enumerate() {
+            for (j, cell) in row.iter_mut().enumerate() {
+                if i != j {
+                    *cell = ((i.min(j) * 7 + i.max(j) * 13) % 5) as f64 - 2.0;
+                }
+            }
+        }
+        let plugin = TriadCounterPlugin::from_matrix(matrix.clone());
+        assert_eq!(plugin.recommended_storage(), Storage::UpperTriangular);
+
+        let packed = UpperTriangularSigns::from_matrix(matrix).unwrap();
+        assert_eq!(packed.len(), n * (n - 1) / 2);
+        assert_eq!(packed.sign(5, 2), plugin.pair_sign(2, 5));
+        assert_eq!(packed.count_triads(), plugin.count_triads_sequential());
+
+        let from_plugin = UpperTriangularSigns::from_plugin(&plugin).unwrap();
+        assert_eq!(from_plugin.count_triads(), plugin.count_triads_sequential());
+
+        let asymmetric = vec![vec![0.0, 1.0], vec![-1.0, 0.0]];
+        let plugin = TriadCounterPlugin::from_matrix(asymmetric.clone());
+        assert!(!plugin.is_symmetric());
+        assert_eq!(plugin.recommended_storage(), Storage::Full);
+        assert!(matches!(
+            UpperTriangularSigns::from_matrix(asymmetric),
+            Err(TriadError::AsymmetricMatrix { row: 0, col: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_bitset_matches_sequential() {
+        // Sizes straddling the 64-bit word and 32-lane chunk boundaries, with
+        // all sign patterns
+        for n in [1, 3, 63, 64, 65, 130] {
+            let mut matrix = vec![vec![0.0; n]; n];
+            for (i, row) in matrix.iter_mut().enumerate() {
+                for (j, cell) in row.iter_mut().enumerate() {
+                    let (a, b) = (i.min(j), i.max(j));
+                    *cell = match (a * 7 + b * 13) % 5 {
+                        0 => 0.0,
+                        1 | 2 => 1.0,
+                        _ => -1.0,
+                    };
+                }
+            }
+            let plugin = TriadCounterPlugin::from_matrix(matrix);
+            let sequential = plugin.count_triads_sequential();
+            assert_eq!(plugin.count_triads_bitset(), sequential, "n = {}", n);
+            assert_eq!(plugin.count_triads_simd(), sequential, "n = {}", n);
+        }
+    }
+
+    #[test]
+    fn test_classify_triad() {
+        let matrix = vec![
+            vec![0.0, 1.0, 1.0, -1.0],
+            vec![1.0, 0.0, -1.0, 0.0],
+            vec![1.0, -1.0, 0.0, -1.0],
+            vec![-1.0, 0.0, -1.0, 0.0],
+        ];
+        let plugin = TriadCounterPlugin::from_matrix(matrix);
+
+        assert_eq!(plugin.classify_triad(0, 1, 2), Some(TriadType::TwoPositive));
+        assert_eq!(plugin.classify_triad(2, 0, 1), Some(TriadType::TwoPositive));
+        assert_eq!(plugin.classify_triad(0, 2, 3), Some(TriadType::OnePositive));
+        assert_eq!(plugin.classify_triad(0, 1, 3), None);
+        assert_eq!(plugin.classify_triad(0, 0, 2), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "out of range")]
+    fn test_classify_triad_out_of_range() {
+        let plugin = TriadCounterPlugin::from_matrix(vec![vec![0.0; 3]; 3]);
+        plugin.classify_triad(0, 1, 3);
+    }
+
+    #[test]
+    fn test_update_edge_matches_recount() {
+        let n = 12;
+        let mut matrix = vec![vec![0.0; n]; n];
+        for (i, row) in matrix.iter_mut().enumerate() {
+            for (j, cell) in row.iter_mut().enumerate() {
+                if i != j && (i * 5 + j * 5) % 4 != 0 {
+                    *cell = if (i + j) % 3 == 0 { -1.0 } else { 1.0 };
+                }
+            }
+        }
+        let mut plugin = TriadCounterPlugin::from_matrix(matrix);
+        plugin.run();
+
+        // Flip, add, and remove edges in turn
+        let flips = [
+            (0, 1, -1.0),
+            (3, 7, 2.0),
+            (0, 1, 1.0),
+            (5, 2, 0.0),
+            (11, 4, -0.5),
+        ];
+        for &(i, j, value) in &flips {
+            let incremental = plugin.update_edge(i, j, value);
+            assert_eq!(incremental, plugin.count_triads_sequential());
+        }
+    }
+
+    #[test]
+    fn test_update_edge_without_current_counts() {
+        let all_positive = || {
+            TriadCounterPlugin::from_matrix(vec![
+                vec![0.0, 1.0, 1.0, 1.0],
+                vec![1.0, 0.0, 1.0, 1.0],
+                vec![1.0, 1.0, 0.0, 1.0],
+                vec![1.0, 1.0, 1.0, 0.0],
+            ])
+        };
+
+        // Before the first run the counts are all zero, not a baseline
+        let mut plugin = all_positive();
+        let counts = plugin.update_edge(0, 1, -1.0);
+        assert_eq!(counts, plugin.count_triads_sequential());
+        assert_eq!(counts.two_positive, 2);
+        assert_eq!(counts.three_positive, 2);
+
+        // Incremental again from there
+        let counts = plugin.update_edge(2, 3, -1.0);
+        assert_eq!(counts, plugin.count_triads_sequential());
+
+        // An option change resets the counts; the next update recounts
+        let mut plugin = all_positive();
+        plugin.run();
+        plugin.set_threshold(0.5);
+        assert_eq!(plugin.counts().total(), 0);
+        assert_eq!(plugin.update_edge(0, 1, 0.2).total(), 2);
+    }
+
+    #[test]
+    fn test_remove_edge_incremental() {
+        let n = 15;
+        let mut matrix = vec![vec![0.0; n]; n];
+        for (i, row) in matrix.iter_mut().enumerate() {
+            for (j, cell) in row.iter_mut().enumerate() {
+                if i != j {
+                    *cell = ((i * 3 + j * 3) % 4) as f64 - 1.5;
+                }
+            }
+        }
+        let mut plugin = TriadCounterPlugin::from_matrix(matrix);
+        plugin.run();
+
+        for &(i, j) in &[(0, 1), (4, 2), (7, 11), (4, 2), (14, 0)] {
+            let incremental = plugin.remove_edge(i, j);
+            assert_eq!(incremental, plugin.count_triads_sequential());
+        }
+    }
+
+    #[test]
+    fn test_remove_edge_on_fresh_load() {
+        let file = create_test_csv("\"\",A,B,C,D\nA,0,1,1,-1\nB,1,0,1,1\nC,1,1,0,1\nD,-1,1,1,0");
+        let mut plugin = TriadCounterPlugin::new();
+        plugin.input(file.path()).unwrap();
+
+        // No run() yet: the stored counts are not a baseline to subtract from
+        let counts = plugin.remove_edge(0, 1);
+        assert_eq!(counts, plugin.count_triads_sequential());
+        assert_eq!(counts.total(), 2);
+        let counts = plugin.remove_edge(2, 3);
+        assert_eq!(counts, plugin.count_triads_sequential());
+        assert_eq!(counts.total(), 0);
+
+        // Counts from before a reload are not reused either
+        plugin.run();
+        plugin.input(file.path()).unwrap();
+        assert_eq!(plugin.remove_edge(1, 3).total(), 2);
+    }
+
+    #[test]
+    fn test_triangle_count_fast() {
+        // Spans more than one 64-bit word per row
+        let n = 90;
+        let mut matrix = vec![vec![0.0; n]; n];
+        for (i, row) in matrix.iter_mut().enumerate() {
+            for (j, cell) in row.iter_mut().enumerate() {
+                if i != j {
+                    *cell = ((i * 5 + j * 5) % 7) as f64 - 3.0;
+                }
+            }
+        }
+        let plugin = TriadCounterPlugin::from_matrix(matrix);
+
+        assert_eq!(
+            plugin.triangle_count_fast(),
+            plugin.count_triads_optimized().total()
+        );
+    }
+
+    #[test]
+    fn test_triad_profile() {
+        let mut plugin = TriadCounterPlugin::from_matrix(vec![
+            vec![0.0, 1.0, 1.0, 1.0],
+            vec![1.0, 0.0, 1.0, -1.0],
+            vec![1.0, 1.0, 0.0, -1.0],
+            vec![1.0, -1.0, -1.0, 0.0],
+        ]);
+        plugin.run();
+
+        // {0,1,2} three, {0,1,3} two, {0,2,3} two, {1,2,3} one
+        let profile = plugin.triad_profile_vector();
+        assert_eq!(profile, [0.25, 0.5, 0.25, 0.0]);
+        assert!((profile.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+
+        let same_shape = TriadCounts {
+            three_positive: 10,
+            two_positive: 20,
+            one_positive: 10,
+            zero_positive: 0,
+        };
+        assert_eq!(profile_distance(plugin.counts(), &same_shape), 0.0);
+
+        let all_stable = TriadCounts {
+            three_positive: 1,
+            ..Default::default()
+        };
+        let all_unstable = TriadCounts {
+            zero_positive: 1,
+            ..Default::default()
+        };
+        assert!((profile_distance(&all_stable, &all_unstable) - 2f64.sqrt()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_symmetrize() {
+        // Upper triangle all-positive, lower triangle mixed
+        let matrix = vec![
+            vec![0.0, 1.0, 2.0],
+            vec![-1.0, 0.0, 1.0],
+            vec![2.0, -3.0, 0.0],
+        ];
+
+        let cases = [
+            (Symmetrization::Upper, TriadType::ThreePositive),
+            (Symmetrization::Lower, TriadType::OnePositive),
+            (Symmetrization::Max, TriadType::ThreePositive),
+            (Symmetrization::Min, TriadType::OnePositive),
+            (Symmetrization::Or, TriadType::TwoPositive),
+        ];
+        for (rule, expected) in cases {
+            let mut plugin = TriadCounterPlugin::from_matrix(matrix.clone());
+            plugin.symmetrize(rule);
+            assert_eq!(
+                plugin.list_triads(),
+                vec![(0, 1, 2, expected)],
+                "{:?}",
+                rule
+            );
+        }
+
+        // Mean cancels the 0-1 edge and leaves 1-2 negative
+        let mut plugin = TriadCounterPlugin::from_matrix(matrix.clone());
+        plugin.run();
+        plugin.symmetrize(Symmetrization::Mean);
+        assert_eq!(plugin.counts().total(), 0);
+        assert_eq!(plugin.pair_sign(2, 1), -1);
+        assert!(plugin.list_triads().is_empty());
+
+        // Repeated calls start from the loaded weights, not the previous rule
+        let mut plugin = TriadCounterPlugin::from_matrix(matrix);
+        for (rule, expected) in cases.iter().chain(cases.iter().rev()) {
+            plugin.symmetrize(*rule);
+            assert_eq!(
+                plugin.list_triads(),
+                vec![(0, 1, 2, *expected)],
+                "{:?}",
+                rule
+            );
+        }
+        plugin.symmetrize(Symmetrization::Mean);
+        assert!(plugin.list_triads().is_empty());
+        plugin.symmetrize(Symmetrization::Upper);
+        // Upper after Mean mirrors the loaded upper triangle, not the means
+        assert_eq!(plugin.adjacency()[3], 1.0);
+        assert_eq!(plugin.adjacency()[7], 1.0);
+
+        // Editing a weight makes the edited matrix the new starting point
+        plugin.update_edge(0, 1, -1.0);
+        plugin.symmetrize(Symmetrization::Lower);
+        assert_eq!(plugin.pair_sign(0, 1), -1);
+        assert_eq!(plugin.pair_sign(0, 2), 1);
+
+        // Or keeps a one-directional edge in both directions
+        let mut plugin = TriadCounterPlugin::from_matrix(vec![
+            vec![0.0, 0.0, 1.0],
+            vec![1.0, 0.0, 0.0],
+            vec![-1.0, 1.0, 0.0],
+        ]);
+        plugin.symmetrize(Symmetrization::Or);
+        assert!(plugin.is_symmetric());
+        assert_eq!(plugin.pair_sign(0, 1), 1);
+        assert_eq!(plugin.pair_sign(1, 2), 1);
+        assert_eq!(plugin.pair_sign(0, 2), 1);
+    }
+
+    #[test]
+    fn test_integrated_balance() {
+        // Strong all-positive triangle {0,1,2}; weak ties to node 3 add one
+        // stable and two unstable triads that vanish at higher thresholds
+        let matrix = vec![
+            vec![0.0, 1.0, 1.0, -0.2],
+            vec![1.0, 0.0, 1.0, -0.2],
+            vec![1.0, 1.0, 0.0, 0.2],
+            vec![-0.2, -0.2, 0.2, 0.0],
+        ];
+        let plugin = TriadCounterPlugin::from_matrix(matrix);
+
+        let sweep = plugin.threshold_sweep(&[0.0, 0.5]);
+        assert_eq!(sweep[0].1.total(), 4);
+        assert_eq!(sweep[0].1.stable(), 2);
+        assert_eq!(sweep[1].1.total(), 1);
+        assert_eq!(sweep[1].1.stable(), 1);
+        assert_eq!(plugin.balance_sweep(&[0.0, 0.5]), sweep);
+
+        // Ratio 0.5 at t = 0, 1.0 at t = 0.5 (unordered input is fine)
+        assert!((plugin.integrated_balance(&[0.5, 0.0]) - 0.75).abs() < 1e-12);
+        assert_eq!(plugin.integrated_balance(&[0.5]), 1.0);
+        assert!(plugin.integrated_balance(&[5.0]).is_nan());
+    }
+
+    #[test]
+    fn test_count_triads_imputed() {
+        // 0-1 is missing; shared neighbors 2 (ties +,+) and 3 (ties +,-)
+        let matrix = vec![
+            vec![0.0, 0.0, 1.0, 1.0],
+            vec![0.0, 0.0, 1.0, -1.0],
+            vec![1.0, 1.0, 0.0, 1.0],
+            vec![1.0, -1.0, 1.0, 0.0],
+        ];
+        let plugin = TriadCounterPlugin::from_matrix(matrix);
+        assert_eq!(plugin.count_triads_optimized().total(), 2);
+
+        // Votes: 3 positive, 1 negative -> imputed positive
+        let majority = plugin.count_triads_imputed(ImputationMethod::NeighborMajority);
+        assert_eq!(majority.total(), 4.0);
+        // Observed {0,2,3} three, {1,2,3} two; imputed {0,1,2} three, {0,1,3} two
+        assert_eq!(majority.three_positive, 2.0);
+        assert_eq!(majority.two_positive, 2.0);
+
+        // Positive with probability 3/4
+        let freq = plugin.count_triads_imputed(ImputationMethod::NeighborFrequency);
+        assert!((freq.total() - 4.0).abs() < 1e-12);
+        assert!((freq.three_positive - 1.75).abs() < 1e-12);
+        assert!((freq.two_positive - 2.0).abs() < 1e-12);
+        assert!((freq.one_positive - 0.25).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_expected_counts_probabilistic() {
+        // Triangle {0,1,2} of coin flips; node 3 only has absent (-1) pairs
+        let matrix = vec![
+            vec![0.0, 0.5, 0.5, -1.0],
+            vec![0.5, 0.0, 0.5, -1.0],
+            vec![0.5, 0.5, 0.0, -1.0],
+            vec![-1.0, -1.0, -1.0, 0.0],
+        ];
+        let mut plugin = TriadCounterPlugin::from_matrix(matrix);
+        plugin.set_missing_edge_policy(MissingEdgePolicy::Sentinel(-1.0));
+        plugin.set_threshold(0.5);
+
+        // Hard signs: 0.5 is not above 0.5, so all negative
+        assert_eq!(plugin.run().zero_positive, 1);
+        assert_eq!(plugin.counts().total(), 1);
+
+        let expected = plugin.expected_counts_probabilistic();
+        assert_eq!(expected.three_positive, 0.125);
+        assert_eq!(expected.two_positive, 0.375);
+        assert_eq!(expected.one_positive, 0.375);
+        assert_eq!(expected.zero_positive, 0.125);
+
+        // Certain probabilities reproduce the hard count
+        let mut plugin = TriadCounterPlugin::from_matrix(vec![
+            vec![0.0, 1.0, 0.0],
+            vec![1.0, 0.0, 1.0],
+            vec![0.0, 1.0, 0.0],
+        ]);
+        plugin.set_missing_edge_policy(MissingEdgePolicy::Sentinel(-1.0));
+        plugin.set_threshold(0.5);
+        assert_eq!(plugin.expected_counts_probabilistic().two_positive, 1.0);
+        assert_eq!(plugin.run().two_positive, 1);
+    }
+
+    #[test]
+    fn test_count_triads_with_sign_fn() {
+        let sign_of = |i: usize, j: usize| match (i * 7 + j * 11) % 3 {
+            0 => 0,
+            1 => 1,
+            _ => -1,
+        };
+
+        for n in [12, 510] {
+            let mut matrix = vec![vec![0.0; n]; n];
+            for (i, row) in matrix.iter_mut().enumerate() {
+                for (j, cell) in row.iter_mut().enumerate() {
+                    if i != j {
+                        *cell = sign_of(i.min(j), i.max(j)) as f64;
+                    }
+                }
+            }
+            let plugin = TriadCounterPlugin::from_matrix(matrix);
+
+            assert_eq!(
+                count_triads_with_sign_fn(n, sign_of),
+                plugin.count_triads_optimized()
+            );
+        }
+    }
+
+    #[test]
+    fn test_count_triads_from_signs() {
+        // Triangle {0,1,2} with signs +,+,- plus an isolated node
+        #[rustfmt::skip]
+        let signs = [
+            0, 1, 1, 0,
+            1, 0, -1, 0,
+            1, -1, 0, 0,
+            0, 0, 0, 0,
+        ];
+        let expected = TriadCounts {
+            two_positive: 1,
+            ..Default::default()
+        };
+        assert_eq!(count_triads_from_signs(&signs, 4), expected);
+        assert_eq!(count_triads_from_signs_parallel(&signs, 4), expected);
+        assert_eq!(count_triads_from_signs(&[], 0), TriadCounts::default());
+    }
+
+    #[test]
+    fn test_clear_reuses_plugin() {
+        let file = create_test_csv("\"\",A,B,C\nA,0,1,1\nB,1,0,1\nC,1,1,0");
+        let mut plugin = TriadCounterPlugin::new();
+        plugin.set_threshold(0.5);
+        plugin.input(file.path()).unwrap();
+        plugin.run();
+
+        let buffer = plugin.adj.as_ptr();
+        plugin.clear();
+        assert_eq!(plugin.node_count(), 0);
+        assert!(plugin.labels().is_empty());
+        assert_eq!(plugin.label_index("A"), None);
+        assert_eq!(plugin.counts().total(), 0);
+        assert_eq!(plugin.threshold(), 0.5);
+
+        // Reloading a same-sized matrix reuses the old allocation
+        plugin.input(file.path()).unwrap();
+        assert_eq!(plugin.adj.as_ptr(), buffer);
+        assert_eq!(plugin.run().three_positive, 1);
+
+        // A failed load leaves the plugin empty rather than half-replaced
+        let bad = create_test_csv("\"\",A,B\nA,0,1");
+        assert!(plugin.input(bad.path()).is_err());
+        assert_eq!(plugin.node_count(), 0);
+    }
+
+    #[test]
+    fn test_label_index() {
+        let csv = "\"\",Alice,Bob,Carol\nAlice,0,1,-1\nBob,1,0,1\nCarol,-1,1,0";
+        let file = create_test_csv(csv);
+
+        let mut plugin = TriadCounterPlugin::new();
+        plugin.input(file.path()).unwrap();
+        assert_eq!(plugin.label_index("Alice"), Some(0));
+        assert_eq!(plugin.label_index("Carol"), Some(2));
+        assert_eq!(plugin.label_index("Dave"), None);
+
+        let plugin = TriadCounterPlugin::from_matrix(vec![vec![0.0; 2]; 2]);
+        assert_eq!(plugin.label_index("Node1"), Some(1));
+    }
+
+    #[test]
+    fn test_unstable_triads() {
+        let csv = "\"\",A,B,C,D\nA,0,1,1,-1\nB,1,0,-1,-1\nC,1,-1,0,-1\nD,-1,-1,-1,0";
+        let file = create_test_csv(csv);
+        let mut plugin = TriadCounterPlugin::new();
+        plugin.input(file.path()).unwrap();
+
+        // ABC: +,+,- and BCD: -,-,- are unstable; ABD and ACD have one positive
+        let unstable: Vec<[&str; 3]> = plugin.unstable_triads().collect();
+        assert_eq!(unstable, vec![["A", "B", "C"], ["B", "C", "D"]]);
+        assert_eq!(
+            unstable.len() as u64,
+            plugin.count_triads_sequential().unstable()
+        );
+    }
+
+    #[test]
+    fn test_balance_theory() {
+        // Two separate triangles: all negative (0, 1, 2) and one positive
+        // (3, 4, 5), with 3-4 the positive edge
+        let mut matrix = vec![vec![0.0; 6]; 6];
+        for (i, j, w) in [
+            (0, 1, -1.0),
+            (0, 2, -1.0),
+            (1, 2, -1.0),
+            (3, 4, 1.0),
+            (3, 5, -1.0),
+            (4, 5, -1.0),
+        ] {
+            matrix[i][j] = w;
+            matrix[j][i] = w;
+        }
+        let plugin = TriadCounterPlugin::from_matrix(matrix);
+        let counts = plugin.count_triads_sequential();
+        assert_eq!((counts.zero_positive, counts.one_positive), (1, 1));
+
+        // Weak, the default, keeps the enemy-of-my-enemy triad stable
+        assert_eq!(BalanceTheory::default(), BalanceTheory::Weak);
+        assert_eq!(counts.stable_under(BalanceTheory::Weak), 1);
+        assert_eq!(counts.unstable_under(BalanceTheory::Weak), 1);
+        assert!(!TriadType::ZeroPositive.is_stable_under(BalanceTheory::Weak));
+        assert!(TriadType::OnePositive.is_stable_under(BalanceTheory::Weak));
+
+        // Strict calls both unstable
+        assert_eq!(counts.stable_under(BalanceTheory::Strict), 0);
+        assert_eq!(counts.unstable_under(BalanceTheory::Strict), 2);
+        assert!(!TriadType::ZeroPositive.is_stable_under(BalanceTheory::Strict));
+        assert!(!TriadType::OnePositive.is_stable_under(BalanceTheory::Strict));
+        assert!(TriadType::ThreePositive.is_stable_under(BalanceTheory::Strict));
+    }
+
+    #[test]
+    fn test_fractions() {
+        let counts = TriadCounts {
+            three_positive: 2,
+            two_positive: 1,
+            one_positive: 1,
+            zero_positive: 0,
+        };
+        assert_eq!(counts.fractions(), [0.5, 0.25, 0.25, 0.0]);
+        assert_eq!(counts.as_array(), [2, 1, 1, 0]);
+
+        let counts = TriadCounts::from_array([2, 1, 1, 3]);
+        assert_eq!(counts.stable_under(BalanceTheory::Weak), counts.stable());
+        assert_eq!(counts.stable_under(BalanceTheory::Strict), 2);
+        assert_eq!(counts.unstable_under(BalanceTheory::Strict), 5);
+        assert!(!TriadType::ZeroPositive.is_stable());
+        assert_eq!(TriadCounts::from_array(counts.as_array()), counts);
+        assert_eq!(TriadCounts::default().fractions(), [0.0; 4]);
+
+        // Percentages with zero triads do not divide by zero
+        let plugin = TriadCounterPlugin::from_matrix(vec![vec![0.0; 3]; 3]);
+        let mut buffer = Vec::new();
+        plugin.output_to(&mut buffer).unwrap();
+        assert!(String::from_utf8(buffer)
+            .unwrap()
+            .contains("\n0: 0 (0.0%)\n"));
+    }
+
+    #[test]
+    fn test_output_to_buffer() {
+        let matrix = vec![
+            vec![0.0, 1.0, 1.0],
+            vec![1.0, 0.0, -1.0],
+            vec![1.0, -1.0, 0.0],
+        ];
+        let mut plugin = TriadCounterPlugin::from_matrix(matrix);
+        plugin.run();
+
+        let mut buffer = Vec::new();
+        plugin.output_to(&mut buffer).unwrap();
+        let text = String::from_utf8(buffer).unwrap();
+        assert!(text.contains("Stable triads: 0\nUnstable triads: 1\n"));
+        assert!(text.contains("\n3: 0 (0.0%)\n2: 1 (100.0%)\n"));
+
+        // The path-based writer produces the same bytes
+        let out = NamedTempFile::new().unwrap();
+        plugin.output(out.path()).unwrap();
+        assert_eq!(std::fs::read_to_string(out.path()).unwrap(), text);
+    }
+
+    #[test]
+    fn test_diff_counts() {
+        let before = TriadCounts::from_array([10, 5, 3, 2]);
+        let after = TriadCounts::from_array([12, 1, 3, 4]);
+        let change = after.diff(&before);
+
+        assert_eq!(change.three_positive, 2);
+        assert_eq!(change.two_positive, -4);
+        assert_eq!(change.stable(), 2);
+        assert_eq!(change.unstable(), -2);
+        assert_eq!(change.to_string(), "+2 stable, -2 unstable");
+        assert_eq!(before.diff(&after), -change);
+
+        let later = TriadCounts::from_array([0, 0, 0, 0]);
+        assert_eq!(change + later.diff(&after), later.diff(&before));
+        assert_eq!(later.diff(&before) - change, later.diff(&after));
+    }
+
+    #[test]
+    fn test_display_counts() {
+        let counts = TriadCounts {
+            three_positive: 2,
+            two_positive: 1,
+            one_positive: 1,
+            zero_positive: 0,
+        };
+        assert_eq!(
+            counts.to_string(),
+            "Stable triads: 3\nUnstable triads: 1\n\nCounts by positive edges:\n\
+             3: 2 (50.0%)\n2: 1 (25.0%)\n1: 1 (25.0%)\n0: 0 (0.0%)"
+        );
+    }
+
+    #[test]
+    fn test_output_append() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("batch.txt");
+
+        let mut first = TriadCounterPlugin::from_matrix(vec![vec![1.0; 3]; 3]);
+        first.run();
+        first.output_append(&path, "first").unwrap();
+        let mut second = TriadCounterPlugin::from_matrix(vec![vec![-1.0; 4]; 4]);
+        second.run();
+        second.output_append(&path, "second").unwrap();
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert!(text.starts_with("Network: first (3 nodes)\n"));
+        assert!(text.contains("Network: second (4 nodes)\n"));
+        assert!(text.contains("Stable triads: 1\n"));
+        assert!(text.contains("Unstable triads: 4\n"));
+    }
+
+    #[test]
+    fn test_output_triads_csv() {
+        let csv = "\"\",A,B,C,D\nA,0,1,1,-1\nB,1,0,1,0\nC,1,1,0,-1\nD,-1,0,-1,0";
+        let file = create_test_csv(csv);
+        let out = NamedTempFile::new().unwrap();
+
+        let mut plugin = TriadCounterPlugin::new();
+        plugin.input(file.path()).unwrap();
+        plugin.output_triads_csv(out.path()).unwrap();
+
+        let written = std::fs::read_to_string(out.path()).unwrap();
+        assert_eq!(
+            written,
+            "node_a,node_b,node_c,positive_edges,stable\n\
+             A,B,C,3,true\n\
+             A,C,D,1,true\n"
+        );
+    }
+
+    #[test]
+    fn test_reciprocity_required() {
+        // Directed: A<->B agree (+), A->C only, B->C and C->B disagree
+        let matrix = vec![
+            vec![0.0, 1.0, 1.0],
+            vec![1.0, 0.0, 1.0],
+            vec![0.0, -1.0, 0.0],
+        ];
+        let mut plugin = TriadCounterPlugin::from_matrix(matrix);
+        assert_eq!(plugin.count_triads_sequential().three_positive, 1);
+
+        plugin.set_reciprocity_required(true);
+        assert_eq!(plugin.pair_sign(0, 1), 1);
+        assert_eq!(plugin.pair_sign(0, 2), 0);
+        assert_eq!(plugin.pair_sign(1, 2), 0);
+        assert_eq!(plugin.count_triads_sequential().total(), 0);
+
+        // Fully reciprocated negative ties survive
+        let matrix = vec![
+            vec![0.0, -1.0, -2.0],
             vec![-1.0, 0.0, -1.0],
-            vec![-1.0, -1.0, 0.0],
+            vec![-0.5, -3.0, 0.0],
+        ];
+        let mut plugin = TriadCounterPlugin::from_matrix(matrix);
+        plugin.set_reciprocity_required(true);
+        assert_eq!(plugin.count_triads_sequential().zero_positive, 1);
+    }
+
+    #[test]
+    fn test_missing_edge_sentinel() {
+        // 0 is a hostile tie, -999 marks the only absent pair (B-D)
+        let csv = "\"\",A,B,C,D\nA,0,1,0,1\nB,1,0,0,-999\nC,0,0,0,1\nD,1,-999,1,0";
+        let file = create_test_csv(csv);
+
+        let mut plugin = TriadCounterPlugin::new();
+        plugin.input(file.path()).unwrap();
+        // By default the zeros are absent and -999 is just a strong negative
+        let counts = plugin.count_triads_optimized();
+        assert_eq!((counts.two_positive, counts.total()), (1, 1));
+
+        plugin.set_missing_edge_policy(MissingEdgePolicy::Sentinel(-999.0));
+        plugin.run();
+        // ABC: +,0,0 -> one positive; ACD: 0,+,+ -> two positive
+        assert_eq!(plugin.counts().one_positive, 1);
+        assert_eq!(plugin.counts().two_positive, 1);
+        assert_eq!(plugin.counts().total(), 2);
+        assert_eq!(plugin.classify_triad(0, 0, 1), None);
+
+        // Removing an edge writes the sentinel, not a hostile 0.0
+        let counts = plugin.remove_edge(0, 2);
+        assert_eq!(counts.total(), 0);
+        assert_eq!(counts, plugin.count_triads_sequential());
+    }
+
+    #[test]
+    fn test_missing_edge_sentinel_loaders() {
+        // A positive path A-B-C closed by a negative A-C; D is isolated
+        let check = |plugin: &mut TriadCounterPlugin| {
+            let summary = plugin.network_summary();
+            assert_eq!(summary.positive_edges, 2);
+            assert_eq!(summary.negative_edges, 1);
+            assert_eq!(summary.zero_entries, 3);
+            let counts = plugin.run();
+            assert_eq!((counts.two_positive, counts.total()), (1, 1));
+        };
+        let sentinel = MissingEdgePolicy::Sentinel(-999.0);
+
+        let edgelist = create_test_csv("A,B,1\nB,C,1\nA,C,-1\nC,D,x");
+        let mut plugin = TriadCounterPlugin::new();
+        plugin.set_missing_edge_policy(sentinel);
+        plugin.input_edgelist(edgelist.path()).unwrap();
+        check(&mut plugin);
+
+        let dimacs = create_test_csv("p edge 4 3\ne 1 2 1\ne 2 3 1\ne 1 3 -1\n");
+        plugin.input_dimacs(dimacs.path()).unwrap();
+        check(&mut plugin);
+
+        // Layer cells holding the sentinel are outside the layer as well
+        let positive =
+            create_test_csv("\"\",A,B,C,D\nA,0,1,0,-999\nB,1,0,1,0\nC,0,1,0,0\nD,-999,0,0,0");
+        let negative = create_test_csv("\"\",A,C\nA,0,1\nC,1,0");
+        plugin
+            .input_layered(positive.path(), negative.path())
+            .unwrap();
+        check(&mut plugin);
+
+        // Top-1 keeps A-B, B-C, and B-D; the dropped A-C and A-D take the
+        // sentinel rather than becoming negative ties
+        let matrix = create_test_csv(
+            "\"\",A,B,C,D\nA,0,3,-2,0.1\nB,3,0,3,0.2\nC,-2,3,0,-999\nD,0.1,0.2,-999,0",
+        );
+        plugin.input(matrix.path()).unwrap();
+        let summary = plugin.sparsify_topk(1).network_summary();
+        assert_eq!(summary.positive_edges, 3);
+        assert_eq!(summary.negative_edges, 0);
+        assert_eq!(summary.zero_entries, 3);
+    }
+
+    #[test]
+    fn test_expected_counts_random() {
+        // K4 with a positive triangle 0-1-2 and negative spokes to node 3
+        let matrix = vec![
+            vec![0.0, 1.0, 1.0, -1.0],
+            vec![1.0, 0.0, 1.0, -1.0],
+            vec![1.0, 1.0, 0.0, -1.0],
+            vec![-1.0, -1.0, -1.0, 0.0],
+        ];
+        let plugin = TriadCounterPlugin::from_matrix(matrix);
+        let expected = plugin.expected_counts_random();
+
+        // p = 1/2 over 4 triangles: 4 * [1, 3, 3, 1] / 8
+        assert_eq!(expected.three_positive, 0.5);
+        assert_eq!(expected.two_positive, 1.5);
+        assert_eq!(expected.one_positive, 1.5);
+        assert_eq!(expected.zero_positive, 0.5);
+        assert_eq!(expected.total(), 4.0);
+
+        let empty = TriadCounterPlugin::from_matrix(vec![vec![0.0; 3]; 3]);
+        assert_eq!(empty.expected_counts_random().total(), 0.0);
+    }
+
+    #[test]
+    fn test_network_summary() {
+        let matrix = vec![
+            vec![0.0, 1.0, -1.0, 0.0],
+            vec![1.0, 0.0, 2.0, 0.0],
+            vec![-1.0, 2.0, 0.0, -0.5],
+            vec![0.0, 0.0, -0.5, 0.0],
+        ];
+        let plugin = TriadCounterPlugin::from_matrix(matrix);
+        let summary = plugin.network_summary();
+
+        assert_eq!(summary.positive_edges, 2);
+        assert_eq!(summary.negative_edges, 2);
+        assert_eq!(summary.zero_entries, 2);
+        assert_eq!(summary.edges(), 4);
+        assert!((summary.density - 4.0 / 6.0).abs() < 1e-12);
+
+        let empty = TriadCounterPlugin::from_matrix(vec![vec![0.0]]);
+        assert_eq!(empty.network_summary().density, 0.0);
+    }
+
+    #[test]
+    fn test_node_degrees() {
+        let matrix = vec![
+            vec![0.0, 1.0, -1.0, 0.0],
+            vec![1.0, 0.0, 2.0, 0.0],
+            vec![-1.0, 2.0, 0.0, -0.5],
+            vec![0.0, 0.0, -0.5, 0.0],
+        ];
+        let plugin = TriadCounterPlugin::from_matrix(matrix);
+        assert_eq!(plugin.node_degrees(), vec![(1, 1), (2, 0), (1, 2), (0, 1)]);
+    }
+
+    #[test]
+    fn test_choose3() {
+        assert_eq!(choose3(2), 0);
+        assert_eq!(choose3(3), 1);
+        assert_eq!(choose3(20), 1140);
+        // Fits in u64 although n(n - 1)(n - 2) does not; larger counts saturate
+        assert_eq!(choose3(3_000_000), 4_499_995_500_001_000_000);
+        assert_eq!(choose3(5_000_000), u64::MAX);
+        assert_eq!(choose3(usize::MAX), u64::MAX);
+    }
+
+    #[test]
+    fn test_estimate_work() {
+        let matrix = vec![
+            vec![0.0, 1.0, -1.0, 0.0],
+            vec![1.0, 0.0, 2.0, 0.0],
+            vec![-1.0, 2.0, 0.0, -0.5],
+            vec![0.0, 0.0, -0.5, 0.0],
+        ];
+        let estimate = TriadCounterPlugin::from_matrix(matrix).estimate_work();
+
+        assert_eq!(estimate.candidate_triads, 4);
+        assert_eq!(estimate.edges, 4);
+        assert!((estimate.skip_rate - 2.0 / 6.0).abs() < 1e-12);
+        // Pairs (0,1), (0,2), (1,2), (2,3) leave 2, 1, 1, 0 third nodes
+        assert_eq!(estimate.inner_checks, 4);
+        assert!((estimate.expected_triads - 4.0 * (4.0f64 / 6.0).powi(3)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_input_spanning_parse_batches() {
+        // More rows than one parse batch, so row order across batches matters
+        let n = PARSE_BATCH_ROWS + 44;
+        let value = |i: usize, j: usize| {
+            if i == j {
+                0
+            } else {
+                ((i.min(j) * 7 + i.max(j) * 13) % 3) as i32 - 1
+            }
+        };
+        let mut csv = String::from("\"\"");
+        for j in 0..n {
+            csv.push_str(&format!(",N{}", j));
+        }
+        for i in 0..n {
+            csv.push_str(&format!("\nN{}", i));
+            for j in 0..n {
+                csv.push_str(&format!(",{}", value(i, j)));
+            }
+        }
+        let file = create_test_csv(&csv);
+
+        let mut plugin = TriadCounterPlugin::new();
+        plugin.input(file.path()).unwrap();
+        let matrix: Vec<Vec<f64>> = (0..n)
+            .map(|i| (0..n).map(|j| value(i, j) as f64).collect())
+            .collect();
+        assert_eq!(
+            plugin.count_triads_sequential(),
+            TriadCounterPlugin::from_matrix(matrix).count_triads_sequential()
+        );
+
+        // Two bad cells in the second batch: the earliest one is reported
+        let bad_row = PARSE_BATCH_ROWS + 10;
+        let lines: Vec<String> = csv
+            .lines()
+            .enumerate()
+            .map(|(line, text)| match line {
+                l if l == bad_row + 1 => text.replacen(",1", ",x", 1),
+                l if l == bad_row + 2 => text.replacen(",0", ",y", 1),
+                _ => text.to_string(),
+            })
+            .collect();
+        let file = create_test_csv(&lines.join("\n"));
+        plugin.set_strict(true);
+        match plugin.input(file.path()).unwrap_err() {
+            TriadError::InvalidValue { row, value, .. } => {
+                assert_eq!(row, bad_row);
+                assert_eq!(value, "x");
+            }
+            other => panic!("unexpected error: {}", other),
+        }
+    }
+
+    #[test]
+    fn test_strict_parsing() {
+        let csv = "\"\",A,B,C\nA,0,1,1\nB,1,0,abc\nC,1,1,0";
+        let file = create_test_csv(csv);
+
+        // Lenient by default: the bad cell is read as no edge
+        let mut plugin = TriadCounterPlugin::new();
+        plugin.input(file.path()).unwrap();
+        assert_eq!(plugin.count_triads_optimized().total(), 0);
+
+        plugin.set_strict(true);
+        match plugin.input(file.path()).unwrap_err() {
+            TriadError::InvalidValue { row, col, value } => {
+                assert_eq!((row, col), (1, 2));
+                assert_eq!(value, "abc");
+            }
+            other => panic!("unexpected error: {}", other),
+        }
+    }
+
+    #[test]
+    fn test_non_finite_values() {
+        let csv = "\"\",A,B,C\nA,0,1,NaN\nB,1,0,1\nC,1,inf,0";
+        let file = create_test_csv(csv);
+
+        let mut plugin = TriadCounterPlugin::new();
+        let err = plugin.input(file.path()).unwrap_err();
+        assert!(matches!(
+            err,
+            TriadError::NonFiniteValue { row: 0, col: 2, value } if value.is_nan()
+        ));
+
+        plugin.set_non_finite_policy(NonFinitePolicy::Zero);
+        plugin.input(file.path()).unwrap();
+        assert_eq!(plugin.count_triads_optimized().total(), 0);
+
+        let matrix = vec![
+            vec![0.0, f64::INFINITY, 1.0],
+            vec![1.0, 0.0, 1.0],
+            vec![1.0, 1.0, 0.0],
+        ];
+        let err = TriadCounterPlugin::try_from_matrix(matrix.clone()).err();
+        assert!(matches!(
+            err,
+            Some(TriadError::NonFiniteValue { row: 0, col: 1, .. })
+        ));
+        // The infallible constructor drops the edge instead
+        let plugin = TriadCounterPlugin::from_matrix(matrix);
+        assert_eq!(plugin.count_triads_optimized().total(), 0);
+
+        let err = TriadCounterPlugin::from_flat(&[0.0, f64::NAN, 1.0, 0.0], 2).err();
+        assert!(matches!(
+            err,
+            Some(TriadError::NonFiniteValue { row: 0, col: 1, .. })
+        ));
+    }
+
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn test_input_binary() {
+        let dir = tempfile::tempdir().unwrap();
+        let matrix = [0.0, 1.0, -1.0, 1.0, 0.0, 1.0, -1.0, 1.0, 0.0f64];
+        let bytes: Vec<u8> = matrix.iter().flat_map(|v| v.to_le_bytes()).collect();
+        let bin = dir.path().join("net.bin");
+        std::fs::write(&bin, &bytes).unwrap();
+
+        let mut plugin = TriadCounterPlugin::new();
+        plugin.input_binary(&bin, 3).unwrap();
+        assert_eq!(plugin.labels(), &["Node0", "Node1", "Node2"]);
+        assert_eq!(plugin.run().two_positive, 1);
+
+        std::fs::write(dir.path().join("net.labels"), "A\nB\nC\n").unwrap();
+        plugin.input_binary(&bin, 3).unwrap();
+        assert_eq!(plugin.label_index("C"), Some(2));
+
+        assert!(matches!(
+            plugin.input_binary(&bin, 2),
+            Err(TriadError::DimensionMismatch {
+                expected: 4,
+                found: 9
+            })
+        ));
+        // A failed load does not leave the previous network in place
+        assert_eq!(plugin.node_count(), 0);
+        assert!(plugin.labels().is_empty());
+
+        // n * n * 8 wraps to 0 for n = 2^62 on 64-bit; that must not match an empty file
+        let empty = dir.path().join("empty.bin");
+        std::fs::write(&empty, []).unwrap();
+        assert!(matches!(
+            plugin.input_binary(&empty, (usize::MAX >> 2) + 1),
+            Err(TriadError::DimensionMismatch { found: 0, .. })
+        ));
+        assert!(matches!(
+            plugin.input_binary(&bin, usize::MAX),
+            Err(TriadError::DimensionMismatch { found: 9, .. })
+        ));
+
+        plugin.input_binary(&bin, 3).unwrap();
+        std::fs::write(dir.path().join("net.labels"), "A\nB\n").unwrap();
+        assert!(plugin.input_binary(&bin, 3).is_err());
+        assert_eq!(plugin.node_count(), 0);
+        assert!(plugin
+            .input_binary(dir.path().join("missing.bin"), 3)
+            .is_err());
+        assert_eq!(plugin.node_count(), 0);
+    }
+
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn test_input_binary_signs_only() {
+        let dir = tempfile::tempdir().unwrap();
+        // 0-2 is asymmetric: -1 above the diagonal, NaN below
+        let matrix = [0.0, 2.0, -1.0, 2.0, 0.0, 0.5, f64::NAN, 0.5, 0.0f64];
+        let bytes: Vec<u8> = matrix.iter().flat_map(|v| v.to_le_bytes()).collect();
+        let bin = dir.path().join("net.bin");
+        std::fs::write(&bin, &bytes).unwrap();
+
+        let mut plugin = TriadCounterPlugin::new();
+        assert!(matches!(
+            plugin.input_binary(&bin, 3),
+            Err(TriadError::NonFiniteValue { row: 2, col: 0, .. })
+        ));
+
+        // The weights stay in the file; only the signs are loaded
+        plugin.set_non_finite_policy(NonFinitePolicy::Zero);
+        plugin.input_binary(&bin, 3).unwrap();
+        assert!(!plugin.has_adjacency());
+        assert!(plugin.adjacency().is_empty());
+        assert_eq!(plugin.run().one_positive, 0);
+        assert_eq!(plugin.counts().two_positive, 1);
+
+        // Options that read the weights are set before loading
+        let mut plugin = TriadCounterPlugin::new();
+        plugin.set_non_finite_policy(NonFinitePolicy::Zero);
+        plugin.set_reciprocity_required(true);
+        plugin.input_binary(&bin, 3).unwrap();
+        assert_eq!(plugin.run().total(), 0);
+
+        let mut plugin = TriadCounterPlugin::new();
+        plugin.set_non_finite_policy(NonFinitePolicy::Zero);
+        plugin
+            .set_symmetry_policy(SymmetryPolicy::RequireSymmetric)
+            .unwrap();
+        assert!(matches!(
+            plugin.input_binary(&bin, 3),
+            Err(TriadError::AsymmetricMatrix { row: 0, col: 2, .. })
+        ));
+
+        // Rewriting the matrix needs the weights, so they are decoded
+        let mut plugin = TriadCounterPlugin::new();
+        plugin.set_non_finite_policy(NonFinitePolicy::Zero);
+        plugin
+            .set_symmetry_policy(SymmetryPolicy::UseUpper)
+            .unwrap();
+        plugin.input_binary(&bin, 3).unwrap();
+        assert!(plugin.has_adjacency());
+        assert_eq!(plugin.adjacency()[6], -1.0);
+        assert_eq!(plugin.run().two_positive, 1);
+    }
+
+    #[cfg(feature = "petgraph")]
+    #[test]
+    fn test_from_petgraph() {
+        let mut graph = petgraph::Graph::<String, f64, petgraph::Undirected>::new_undirected();
+        let a = graph.add_node("A".to_string());
+        let b = graph.add_node("B".to_string());
+        let c = graph.add_node("C".to_string());
+        graph.add_edge(a, b, 1.0);
+        graph.add_edge(b, c, -2.0);
+        graph.add_edge(c, a, 0.5);
+
+        let plugin = TriadCounterPlugin::from_petgraph_labeled(&graph);
+        assert_eq!(plugin.labels(), &["A", "B", "C"]);
+        assert_eq!(plugin.count_triads_sequential().two_positive, 1);
+
+        let plugin = TriadCounterPlugin::from_petgraph(&graph);
+        assert_eq!(plugin.label_index("Node2"), Some(2));
+
+        // A directed edge only fills its own direction
+        let mut directed = petgraph::Graph::<(), f64>::new();
+        let (x, y) = (directed.add_node(()), directed.add_node(()));
+        directed.add_edge(y, x, -1.0);
+        let plugin = TriadCounterPlugin::from_petgraph(&directed);
+        assert!(!plugin.is_symmetric());
+    }
+
+    #[test]
+    fn test_edge_frustration_scores() {
+        // Triangle 0-1-2 is unstable (all negative); 0-1-3 has one negative edge
+        // and two positives, also unstable; edge 0-1 is in both
+        let matrix = vec![
+            vec![0.0, -1.0, -1.0, 1.0],
+            vec![-1.0, 0.0, -1.0, 1.0],
+            vec![-1.0, -1.0, 0.0, 0.0],
+            vec![1.0, 1.0, 0.0, 0.0],
+        ];
+        let plugin = TriadCounterPlugin::from_matrix(matrix);
+        let scores = plugin.edge_frustration_scores();
+
+        assert_eq!(scores.len(), 5);
+        assert_eq!(scores[0], ((0, 1), 2));
+        assert!(scores[1..].iter().all(|&(_, score)| score == 1));
+        assert_eq!(scores[1].0, (0, 2));
+        let total: u64 = scores.iter().map(|&(_, score)| score).sum();
+        assert_eq!(total, 3 * plugin.count_triads_sequential().unstable());
+    }
+
+    #[test]
+    fn test_remove_nodes() {
+        let content = r#""",A,Env,B,C
+A,0,1,1,-1
+Env,1,0,-1,-1
+B,1,-1,0,1
+C,-1,-1,1,0
+"#;
+        let file = create_test_csv(content);
+        let mut plugin = TriadCounterPlugin::new();
+        plugin.input(file.path()).unwrap();
+        plugin.run();
+        assert_eq!(plugin.counts().total(), 4);
+
+        plugin.remove_nodes(&[1, 1]);
+        assert_eq!(plugin.node_count(), 3);
+        assert_eq!(plugin.labels(), &["A", "B", "C"]);
+        assert_eq!(plugin.label_index("C"), Some(2));
+        assert_eq!(plugin.label_index("Env"), None);
+        assert_eq!(plugin.counts().total(), 0);
+        assert_eq!(plugin.classify_triad(0, 1, 2), Some(TriadType::TwoPositive));
+    }
+
+    #[test]
+    fn test_count_triads_by_partition() {
+        let matrix = vec![
+            vec![0.0, 1.0, 1.0, -1.0],
+            vec![1.0, 0.0, 1.0, -1.0],
+            vec![1.0, 1.0, 0.0, 1.0],
+            vec![-1.0, -1.0, 1.0, 0.0],
         ];
-        let mut plugin = TriadCounterPlugin::from_matrix(matrix);
-        plugin.run();
+        let plugin = TriadCounterPlugin::from_matrix(matrix);
+        let by_groups = plugin.count_triads_by_partition(&[0, 0, 0, 1]);
 
-        assert_eq!(plugin.counts().zero_positive, 1);
-        assert_eq!(plugin.counts().stable(), 0);
-        assert_eq!(plugin.counts().unstable(), 1);
+        assert_eq!(by_groups.len(), 2);
+        assert_eq!(by_groups[&[0, 0, 0]].three_positive, 1);
+        let cross = &by_groups[&[0, 0, 1]];
+        assert_eq!(cross.total(), 3);
+        assert_eq!(cross.one_positive, 1);
+        assert_eq!(cross.two_positive, 2);
     }
 
     #[test]
-    fn test_one_positive_triad() {
-        // 3 nodes, 1 positive 2 negative -> 1 stable triad
+    fn test_input_limited() {
+        let content = r#""",A,B,C,D
+A,0,1,1,x
+B,1,0,-1,1
+C,1,-1,0,1
+D,x,1,1,0
+"#;
+        let file = create_test_csv(content);
+        let mut plugin = TriadCounterPlugin::new();
+        plugin.set_strict(true);
+        // The unparseable D column and row are never read
+        plugin.input_limited(file.path(), 3).unwrap();
+        assert_eq!(plugin.labels(), &["A", "B", "C"]);
+        assert_eq!(plugin.run().two_positive, 1);
+
+        plugin.input_limited(file.path(), 10).unwrap_err();
+        plugin.set_strict(false);
+        plugin.input_limited(file.path(), 10).unwrap();
+        assert_eq!(plugin.node_count(), 4);
+    }
+
+    #[test]
+    fn test_keep_diagonal() {
+        let content = "\"\",A,B,C\nA,0.9,1,1\nB,1,0.5,-1\nC,1,-1,0.7\n";
+        let mut plugin = TriadCounterPlugin::new();
+        plugin.input_str(content).unwrap();
+        assert_eq!(plugin.adjacency()[4], 0.0);
+
+        plugin.set_keep_diagonal(true);
+        plugin.input_str(content).unwrap();
+        assert_eq!(plugin.adjacency()[0], 0.9);
+        assert_eq!(plugin.adjacency()[4], 0.5);
+        assert_eq!(plugin.adjacency()[8], 0.7);
+        assert_eq!(plugin.run().two_positive, 1);
+        assert_eq!(plugin.network_summary().edges(), 3);
+    }
+
+    #[test]
+    fn test_symmetry_report() {
+        // Lower triangle dropped except for the 0-1 pair
         let matrix = vec![
             vec![0.0, 1.0, -1.0],
-            vec![1.0, 0.0, -1.0],
-            vec![-1.0, -1.0, 0.0],
+            vec![1.0, 0.0, 0.5],
+            vec![0.0, 0.0, 0.0],
+        ];
+        let plugin = TriadCounterPlugin::from_matrix(matrix);
+        assert_eq!(
+            plugin.symmetry_report(),
+            vec![(0, 2, -1.0, 0.0), (1, 2, 0.5, 0.0)]
+        );
+
+        let symmetric = TriadCounterPlugin::from_matrix(vec![vec![1.0; 3]; 3]);
+        assert!(symmetric.symmetry_report().is_empty());
+    }
+
+    #[test]
+    fn test_checkpointed_count_resumes() {
+        let plugin = TriadCounterPlugin::random(40, 0.6, 0.5, 11);
+        let full = plugin.count_triads_sequential();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("count.ckpt");
+
+        assert_eq!(plugin.count_triads_checkpointed(&path, 7).unwrap(), full);
+        let saved = CountCheckpoint::load(&path).unwrap();
+        assert!(saved.is_complete());
+        assert_eq!(saved.counts, full);
+
+        // Simulate a run killed after the first half of the rows
+        let mut half = TriadCounts::default();
+        for i in 0..20 {
+            count_signs_row(&plugin.signs, 40, i, &mut half);
+        }
+        let interrupted = CountCheckpoint {
+            node_count: 40,
+            next_row: 20,
+            counts: half,
+        };
+        interrupted.save(&path).unwrap();
+        assert_eq!(plugin.resume_from_checkpoint(&path, 3).unwrap(), full);
+        assert_eq!(plugin.resume_from_checkpoint(&path, 3).unwrap(), full);
+
+        let other = TriadCounterPlugin::random(30, 0.6, 0.5, 11);
+        assert!(matches!(
+            other.resume_from_checkpoint(&path, 3),
+            Err(TriadError::Malformed { line: 2, .. })
+        ));
+        std::fs::write(&path, "nodes=40\n").unwrap();
+        assert!(matches!(
+            CountCheckpoint::load(&path),
+            Err(TriadError::Malformed { line: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_read_labels() {
+        // The unparseable body is never read
+        let file = create_test_csv("\"\",A,B,C\nA,x,y,z\n");
+        assert_eq!(read_labels(file.path()).unwrap(), vec!["A", "B", "C"]);
+
+        let empty = create_test_csv("\"\"\nA\n");
+        assert!(matches!(
+            read_labels(empty.path()),
+            Err(TriadError::EmptyMatrix)
+        ));
+    }
+
+    #[test]
+    fn test_free_adjacency() {
+        let mut plugin = TriadCounterPlugin::random(12, 0.5, 0.8, 3);
+        let expected = plugin.count_triads_sequential();
+        plugin.free_adjacency();
+
+        assert!(!plugin.has_adjacency());
+        assert_eq!(plugin.count_triads_optimized(), expected);
+        assert!(matches!(
+            plugin.set_symmetry_policy(SymmetryPolicy::RequireSymmetric),
+            Err(TriadError::WeightsFreed)
+        ));
+        for policy in [
+            SymmetryPolicy::UseUpper,
+            SymmetryPolicy::UseLower,
+            SymmetryPolicy::Average,
+        ] {
+            assert!(matches!(
+                plugin.set_symmetry_policy(policy),
+                Err(TriadError::WeightsFreed)
+            ));
+        }
+        assert_eq!(plugin.count_triads_optimized(), expected);
+
+        // Sign options wait for the next load instead of panicking
+        plugin.set_threshold(10.0);
+        plugin.set_reciprocity_required(true);
+        plugin.set_missing_edge_policy(MissingEdgePolicy::Sentinel(-1.0));
+        assert_eq!(plugin.threshold(), 10.0);
+        assert_eq!(plugin.count_triads_optimized(), expected);
+        assert!(plugin.is_symmetric());
+        plugin.set_threshold(0.0);
+        plugin.set_reciprocity_required(false);
+        plugin.set_missing_edge_policy(MissingEdgePolicy::Zero);
+
+        assert!(matches!(
+            UpperTriangularSigns::from_plugin(&plugin),
+            Err(TriadError::WeightsFreed)
+        ));
+        plugin.remove_nodes(&[0]);
+        assert_eq!(plugin.node_count(), 11);
+
+        plugin.input_str("\"\",A,B\nA,0,1\nB,1,0\n").unwrap();
+        assert!(plugin.has_adjacency());
+    }
+
+    #[test]
+    #[should_panic(expected = "weighted_balance_sum needs edge weights")]
+    fn test_weighted_balance_sum_after_free() {
+        let mut plugin = TriadCounterPlugin::from_matrix(vec![vec![1.0; 3]; 3]);
+        plugin.free_adjacency();
+        plugin.weighted_balance_sum();
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_into_signs_only() {
+        let mut plugin = TriadCounterPlugin::random(30, 0.5, 0.6, 5);
+        let expected = plugin.count_triads_sequential();
+
+        plugin.into_signs_only();
+        assert!(plugin.adjacency().is_empty());
+        assert_eq!(plugin.run(), &expected);
+        assert_eq!(plugin.count_triads_bitset(), expected);
+        assert_eq!(
+            plugin.network_summary().edges(),
+            plugin.estimate_work().edges
+        );
+    }
+
+    #[test]
+    fn test_count_triads_min_weight() {
+        // Strong triangle {0,1,2}; node 3 joins through weak ties
+        let matrix = vec![
+            vec![0.0, 2.0, -1.5, 0.2],
+            vec![2.0, 0.0, -3.0, -0.3],
+            vec![-1.5, -3.0, 0.0, 0.1],
+            vec![0.2, -0.3, 0.1, 0.0],
+        ];
+        let plugin = TriadCounterPlugin::from_matrix(matrix);
+        assert_eq!(
+            plugin.count_triads_min_weight(0.0),
+            plugin.count_triads_sequential()
+        );
+
+        let strong = plugin.count_triads_min_weight(1.5);
+        assert_eq!(strong.total(), 1);
+        assert_eq!(strong.one_positive, 1);
+        assert_eq!(plugin.count_triads_min_weight(2.5).total(), 0);
+    }
+
+    #[test]
+    fn test_triads_for_edge() {
+        let matrix = vec![
+            vec![0.0, 1.0, 1.0, -1.0],
+            vec![1.0, 0.0, 1.0, -1.0],
+            vec![1.0, 1.0, 0.0, 0.0],
+            vec![-1.0, -1.0, 0.0, 0.0],
         ];
+        let plugin = TriadCounterPlugin::from_matrix(matrix);
+
+        let through = plugin.triads_for_edge(1, 0);
+        assert_eq!(through.total(), 2);
+        assert_eq!(through.three_positive, 1);
+        assert_eq!(through.one_positive, 1);
+        assert_eq!(plugin.triads_for_edge(2, 3), TriadCounts::default());
+        assert_eq!(plugin.triads_for_edge(2, 2), TriadCounts::default());
+    }
+
+    #[test]
+    #[should_panic(expected = "out of range")]
+    fn test_triads_for_edge_out_of_range() {
+        let plugin = TriadCounterPlugin::from_matrix(vec![vec![0.0; 3]; 3]);
+        plugin.triads_for_edge(0, 3);
+    }
+
+    #[test]
+    fn test_input_comments_and_blank_lines() {
+        let file = create_test_csv(
+            "# exported by hand\n\"\",A,B,C\nA,0,1,-1\n# B was re-surveyed\n\nB,1,0,1\nC,-1,1,0\n\n\n",
+        );
+        let mut plugin = TriadCounterPlugin::new();
+        plugin.set_strict(true);
+        plugin.input(file.path()).unwrap();
+
+        assert_eq!(plugin.labels(), &["A", "B", "C"]);
+        assert_eq!(plugin.pair_sign(1, 2), 1);
+        assert_eq!(plugin.pair_sign(0, 2), -1);
+        assert_eq!(plugin.run().two_positive, 1);
+        assert_eq!(read_labels(file.path()).unwrap(), vec!["A", "B", "C"]);
+    }
+
+    #[test]
+    fn test_input_headerless() {
+        let file = create_test_csv("0,1,-1\n1,0,1\n-1,1,0\n");
+        let mut plugin = TriadCounterPlugin::new();
+        plugin.input_headerless(file.path()).unwrap();
+
+        assert_eq!(plugin.labels(), &["Node0", "Node1", "Node2"]);
+        assert_eq!(plugin.run().two_positive, 1);
+        assert_eq!(plugin.pair_sign(0, 2), -1);
+
+        let ragged = create_test_csv("0,1,-1\n1,0,1\n");
+        assert!(matches!(
+            plugin.input_headerless(ragged.path()),
+            Err(TriadError::NonSquareMatrix { rows: 2, cols: 3 })
+        ));
+    }
+
+    #[test]
+    fn test_input_layered() {
+        // Positive ties among A, B, C; negative ties from D to A and B
+        let positive = create_test_csv("\"\",A,B,C\nA,0,1,1\nB,1,0,1\nC,1,1,0");
+        let negative = create_test_csv("\"\",B,D,A\nB,0,1,0\nD,1,0,1\nA,0,1,0");
+
+        let mut plugin = TriadCounterPlugin::new();
+        plugin
+            .input_layered(positive.path(), negative.path())
+            .unwrap();
+        assert_eq!(plugin.labels(), &["A", "B", "C", "D"]);
+
+        let counts = plugin.run();
+        // ABC all positive; ABD has A-B positive and two negative spokes
+        assert_eq!(counts.three_positive, 1);
+        assert_eq!(counts.one_positive, 1);
+        assert_eq!(counts.total(), 2);
+
+        let conflicting = create_test_csv("\"\",C,A\nC,0,-1\nA,-1,0");
+        let err = plugin
+            .input_layered(positive.path(), conflicting.path())
+            .unwrap_err();
+        assert!(matches!(err, TriadError::ConflictingEdge { .. }));
+        // The previous network is not kept around after a failed load
+        assert_eq!(plugin.node_count(), 0);
+        assert!(plugin.labels().is_empty());
+        assert_eq!(plugin.counts(), &TriadCounts::default());
+
+        plugin
+            .input_layered(positive.path(), negative.path())
+            .unwrap();
+        assert!(plugin
+            .input_layered(positive.path(), Path::new("/nonexistent/negative.csv"))
+            .is_err());
+        assert_eq!(plugin.node_count(), 0);
+    }
+
+    #[test]
+    fn test_duplicate_label_rejected() {
+        let csv = "\"\",CTRL,A,CTRL\nCTRL,0,1,1\nA,1,0,1\nCTRL,1,1,0";
+        let file = create_test_csv(csv);
+
+        let mut plugin = TriadCounterPlugin::new();
+        let err = plugin.input(file.path()).unwrap_err();
+        match err {
+            TriadError::DuplicateLabel {
+                name,
+                first,
+                second,
+            } => {
+                assert_eq!(name, "CTRL");
+                assert_eq!((first, second), (0, 2));
+            }
+            other => panic!("unexpected error: {}", other),
+        }
+    }
+
+    #[test]
+    fn test_input_str() {
+        let mut plugin = TriadCounterPlugin::new();
+        plugin
+            .input_str("\"\",A,B,C\nA,0,1,-1\nB,1,0,1\nC,-1,1,0")
+            .unwrap();
+        assert_eq!(plugin.labels(), &["A", "B", "C"]);
+        assert_eq!(plugin.run().two_positive, 1);
+
+        assert!(matches!(
+            plugin.input_str("\"\",A,B\nA,0,1"),
+            Err(TriadError::NonSquareMatrix { rows: 1, cols: 2 })
+        ));
+        assert_eq!(plugin.node_count(), 0);
+    }
+
+    #[test]
+    fn test_tab_delimited_input() {
+        let csv = "\"\"\tA\tB\tC\nA\t0\t1\t-1\nB\t1\t0\t1\nC\t-1\t1\t0";
+        let file = create_test_csv(csv);
+
+        let mut plugin = TriadCounterPlugin::new();
+        plugin.set_delimiter(b'\t');
+        assert_eq!(plugin.delimiter(), b'\t');
+        plugin.input(file.path()).unwrap();
+        plugin.run();
+
+        assert_eq!(plugin.labels(), &["A", "B", "C"]);
+        assert_eq!(plugin.counts().two_positive, 1);
+    }
+
+    #[test]
+    fn test_builder_build() {
+        let plugin = TriadCounterBuilder::new().build();
+        assert_eq!(plugin.threshold(), 0.0);
+        assert_eq!(plugin.parallel_threshold(), 500);
+        assert_eq!(plugin.delimiter(), b',');
+
+        let plugin = TriadCounterBuilder::new()
+            .threshold(0.25)
+            .parallel_threshold(64)
+            .delimiter(b'\t')
+            .build();
+        assert_eq!(plugin.threshold(), 0.25);
+        assert_eq!(plugin.parallel_threshold(), 64);
+        assert_eq!(plugin.delimiter(), b'\t');
+
+        let asymmetric = create_test_csv("\"\",A,B\nA,0,1\nB,-1,0");
+        let mut plugin = TriadCounterBuilder::new()
+            .symmetry_policy(SymmetryPolicy::RequireSymmetric)
+            .build();
+        assert!(matches!(
+            plugin.input(asymmetric.path()),
+            Err(TriadError::AsymmetricMatrix { .. })
+        ));
+    }
+
+    #[test]
+    fn test_builder_analyze_file() {
+        // Semicolon-delimited and asymmetric, with a weak upper A-C edge
+        let csv = "\"\";A;B;C\nA;0;1;0.05\nB;1;0;-1\nC;1;-1;0";
+        let file = create_test_csv(csv);
+
+        let counts = TriadCounterBuilder::new()
+            .delimiter(b';')
+            .symmetrization(Symmetrization::Lower)
+            .analyze_file(file.path())
+            .unwrap();
+        assert_eq!(counts.two_positive, 1);
+
+        // Upper value of A-C is 0.05, removed by the threshold
+        let counts = TriadCounterBuilder::new()
+            .delimiter(b';')
+            .threshold(0.1)
+            .parallel_threshold(3)
+            .analyze_file(file.path())
+            .unwrap();
+        assert_eq!(counts.total(), 0);
+
+        let typo = create_test_csv("\"\",A,B\nA,0,abc\nB,1,0");
+        let err = TriadCounterBuilder::new()
+            .strict(true)
+            .analyze_file(typo.path())
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            TriadError::InvalidValue { row: 0, col: 1, .. }
+        ));
+    }
+
+    #[test]
+    fn test_count_triads_per_node() {
+        let n = 16;
+        let mut matrix = vec![vec![0.0; n]; n];
+        for (i, row) in matrix.iter_mut().enumerate() {
+            for (j, cell) in row.iter_mut().enumerate() {
+                if i != j {
+                    *cell = ((i + j) % 3) as f64 - 1.0;
+                }
+            }
+        }
         let mut plugin = TriadCounterPlugin::from_matrix(matrix);
         plugin.run();
 
-        assert_eq!(plugin.counts().one_positive, 1);
-        assert_eq!(plugin.counts().stable(), 1);
+        let per_node = plugin.count_triads_per_node();
+        let sum = per_node.iter().fold(TriadCounts::default(), |mut acc, c| {
+            acc.merge(c);
+            acc
+        });
+        assert_eq!(sum.total(), 3 * plugin.counts().total());
+        assert_eq!(sum.stable(), 3 * plugin.counts().stable());
+        assert_eq!(plugin.count_triads_per_node_parallel(), per_node);
+
+        let labeled = plugin.count_triads_per_node_labeled();
+        assert_eq!(labeled[5].0, "Node5");
+        assert_eq!(labeled[5].1, per_node[5]);
     }
 
     #[test]
-    fn test_two_positive_triad() {
-        // 3 nodes, 2 positive 1 negative -> 1 unstable triad
+    fn test_sign_threshold() {
+        // Near-zero noise of +/-0.05 on two edges of a positive triangle
         let matrix = vec![
-            vec![0.0, 1.0, 1.0],
-            vec![1.0, 0.0, -1.0],
-            vec![1.0, -1.0, 0.0],
+            vec![0.0, 1.0, 0.05],
+            vec![1.0, 0.0, -0.05],
+            vec![0.05, -0.05, 0.0],
         ];
         let mut plugin = TriadCounterPlugin::from_matrix(matrix);
+        assert_eq!(plugin.threshold(), 0.0);
+        plugin.run();
+        assert_eq!(plugin.counts().two_positive, 1);
+
+        plugin.set_threshold(0.1);
+        assert_eq!(plugin.counts().total(), 0);
+        plugin.run();
+        assert_eq!(plugin.counts().total(), 0);
+
+        // Values exactly at the threshold are also dropped
+        plugin.set_threshold(0.05);
         plugin.run();
+        assert_eq!(plugin.counts().total(), 0);
 
+        plugin.set_threshold(0.04);
+        plugin.run();
         assert_eq!(plugin.counts().two_positive, 1);
-        assert_eq!(plugin.counts().unstable(), 1);
     }
 
     #[test]
-    fn test_csv_parsing() {
-        let csv = "\"\",A,B,C\nA,0,1,-1\nB,1,0,1\nC,-1,1,0";
+    fn test_symmetry_policy() {
+        // Upper triangle +,+,+; lower triangle +,-,-
+        let csv = "\"\",A,B,C\nA,0,1,1\nB,1,0,1\nC,-1,-1,0";
         let file = create_test_csv(csv);
+        let load = |policy| {
+            let mut plugin = TriadCounterPlugin::new();
+            plugin.set_symmetry_policy(policy).unwrap();
+            plugin.input(file.path()).map(|_| plugin.run().clone())
+        };
 
+        let err = load(SymmetryPolicy::RequireSymmetric).unwrap_err();
+        assert!(matches!(
+            err,
+            TriadError::AsymmetricMatrix {
+                row: 0,
+                col: 2,
+                upper,
+                lower,
+            } if upper == 1.0 && lower == -1.0
+        ));
+        assert_eq!(load(SymmetryPolicy::UseUpper).unwrap().three_positive, 1);
+        assert_eq!(load(SymmetryPolicy::UseLower).unwrap().one_positive, 1);
+        // Mean cancels A-C and B-C
+        assert_eq!(load(SymmetryPolicy::Average).unwrap().total(), 0);
+
+        // Applied immediately to an already loaded matrix
         let mut plugin = TriadCounterPlugin::new();
         plugin.input(file.path()).unwrap();
+        assert!(plugin
+            .set_symmetry_policy(SymmetryPolicy::RequireSymmetric)
+            .is_err());
+        plugin
+            .set_symmetry_policy(SymmetryPolicy::UseLower)
+            .unwrap();
+        assert_eq!(plugin.run().one_positive, 1);
+    }
 
-        assert_eq!(plugin.node_count(), 3);
-        assert_eq!(plugin.labels(), &["A", "B", "C"]);
+    #[test]
+    fn test_count_with_progress() {
+        use std::sync::Mutex;
+
+        let n = 250;
+        let mut matrix = vec![vec![0.0; n]; n];
+        for (i, row) in matrix.iter_mut().enumerate() {
+            for (j, cell) in row.iter_mut().enumerate() {
+                if i != j {
+                    *cell = ((i * 3 + j * 3) % 5) as f64 - 2.0;
+                }
+            }
+        }
+        let mut plugin = TriadCounterPlugin::from_matrix(matrix);
+        let expected = plugin.count_triads_sequential();
+
+        for parallel_threshold in [usize::MAX, 0] {
+            plugin.config.parallel_threshold = parallel_threshold;
+            let reports = Mutex::new(Vec::new());
+            let counts = plugin.count_triads_with_progress(|done, total| {
+                reports.lock().unwrap().push((done, total));
+            });
+            assert_eq!(counts, expected);
+
+            let reports = reports.into_inner().unwrap();
+            // Every 2 rows out of 250
+            assert_eq!(reports.len(), 125);
+            assert!(reports.iter().all(|&(_, total)| total == n));
+            assert!(reports.contains(&(n, n)));
+        }
     }
 
     #[test]
-    fn test_larger_network() {
-        // 4 nodes = C(4,3) = 4 possible triads
+    fn test_count_triads_cancellable() {
+        let n = 120;
+        let mut matrix = vec![vec![0.0; n]; n];
+        for (i, row) in matrix.iter_mut().enumerate() {
+            for (j, cell) in row.iter_mut().enumerate() {
+                if i != j {
+                    *cell = ((i * 3 + j * 3) % 5) as f64 - 2.0;
+                }
+            }
+        }
+        let mut plugin = TriadCounterPlugin::from_matrix(matrix);
+        let expected = plugin.count_triads_sequential();
+
+        for parallel_threshold in [usize::MAX, 0] {
+            plugin.config.parallel_threshold = parallel_threshold;
+            let cancel = AtomicBool::new(false);
+            assert_eq!(
+                plugin.count_triads_cancellable(&cancel),
+                Some(expected.clone())
+            );
+
+            cancel.store(true, Ordering::Relaxed);
+            assert_eq!(plugin.count_triads_cancellable(&cancel), None);
+        }
+    }
+
+    #[test]
+    fn test_count_triads_subset() {
         let matrix = vec![
-            vec![0.0, 1.0, 1.0, 1.0],
-            vec![1.0, 0.0, 1.0, 1.0],
+            vec![0.0, 1.0, 1.0, -1.0],
+            vec![1.0, 0.0, 1.0, -1.0],
             vec![1.0, 1.0, 0.0, 1.0],
-            vec![1.0, 1.0, 1.0, 0.0],
+            vec![-1.0, -1.0, 1.0, 0.0],
         ];
-        let mut plugin = TriadCounterPlugin::from_matrix(matrix);
-        plugin.run();
+        let plugin = TriadCounterPlugin::from_matrix(matrix);
 
-        // All positive edges -> all triads have 3 positive
-        assert_eq!(plugin.counts().three_positive, 4);
-        assert_eq!(plugin.counts().total(), 4);
+        let subset = plugin.count_triads_subset(&[2, 0, 1, 0]);
+        assert_eq!(subset.three_positive, 1);
+        assert_eq!(subset.total(), 1);
+
+        let all = plugin.count_triads_subset(&[3, 2, 1, 0]);
+        assert_eq!(all, plugin.count_triads_sequential());
+        assert_eq!(plugin.count_triads_subset(&[0, 1]).total(), 0);
     }
 
     #[test]
-    fn test_sequential_vs_parallel() {
-        // Create a moderate network
-        let n = 20;
+    #[should_panic(expected = "out of range")]
+    fn test_count_triads_subset_out_of_range() {
+        let plugin = TriadCounterPlugin::from_matrix(vec![vec![0.0; 3]; 3]);
+        plugin.count_triads_subset(&[0, 1, 3]);
+    }
+
+    #[test]
+    fn test_enumerate_triads() {
+        let n = 13;
         let mut matrix = vec![vec![0.0; n]; n];
-        for i in 0..n {
-            for j in 0..n {
+        for (i, row) in matrix.iter_mut().enumerate() {
+            for (j, cell) in row.iter_mut().enumerate() {
                 if i != j {
-                    matrix[i][j] = if (i + j) % 3 == 0 { -1.0 } else { 1.0 };
+                    *cell = ((i * 5 + j * 5) % 3) as f64 - 1.0;
                 }
             }
         }
+        let plugin = TriadCounterPlugin::from_matrix(matrix);
 
+        let lazy: Vec<_> = plugin
+            .enumerate_triads()
+            .map(|(i, j, k, pos)| (i, j, k, TriadType::from_positive_count(pos)))
+            .collect();
+        assert!(!lazy.is_empty());
+        assert_eq!(lazy, plugin.list_triads());
+
+        let unstable = plugin
+            .enumerate_triads()
+            .filter(|&(_, _, _, pos)| pos % 2 == 0)
+            .count() as u64;
+        assert_eq!(unstable, plugin.count_triads_sequential().unstable());
+
+        assert_eq!(TriadCounterPlugin::new().enumerate_triads().count(), 0);
+    }
+
+    #[test]
+    fn test_parallel_skewed_network() {
+        // Hub nodes 0 and 1 tied to everyone, the rest sparse
+        let n = 120;
+        let mut matrix = vec![vec![0.0; n]; n];
+        for (i, row) in matrix.iter_mut().enumerate() {
+            for (j, cell) in row.iter_mut().enumerate() {
+                if i != j && (i.min(j) < 2 || (i + j) % 9 == 0) {
+                    *cell = if (i * j) % 4 == 0 { -1.0 } else { 1.0 };
+                }
+            }
+        }
         let plugin = TriadCounterPlugin::from_matrix(matrix);
-        let seq = plugin.count_triads_sequential();
-        let par = plugin.count_triads_parallel_chunked();
 
-        assert_eq!(seq, par);
+        assert_eq!(
+            plugin.count_triads_parallel_chunked(),
+            plugin.count_triads_sequential()
+        );
+    }
+
+    #[test]
+    fn test_run_in_pool() {
+        let mut plugin = TriadCounterPlugin::random(60, 0.5, 0.7, 9);
+        plugin.set_parallel_threshold(10);
+        let expected = plugin.count_triads_sequential();
+        assert_eq!(plugin.run_in_pool(2).unwrap(), &expected);
+        assert_eq!(plugin.run_in_pool(0).unwrap(), &expected);
+    }
+
+    #[test]
+    fn test_sparse_signs() {
+        // Ring of 60 nodes with chords from node 0, plus one triangle closed
+        // by the ring itself
+        let n = 60;
+        let mut edges: Vec<(usize, usize, i8)> = (0..n).map(|i| (i, (i + 1) % n, 1)).collect();
+        edges.extend((2..n).step_by(7).map(|k| (0, k, -1)));
+        edges.push((3, 1, -1));
+        let sparse = SparseSigns::from_edges(n, edges.iter().copied());
+
+        let mut matrix = vec![vec![0.0; n]; n];
+        for &(u, v, sign) in &edges {
+            matrix[u][v] = sign as f64;
+            matrix[v][u] = sign as f64;
+        }
+        let mut plugin = TriadCounterPlugin::from_matrix(matrix);
+        assert!(plugin.network_summary().density < SPARSE_DENSITY);
+        assert_eq!(plugin.recommended_storage(), Storage::Sparse);
+        assert_eq!(plugin.counting_strategy(), CountingStrategy::Sparse);
+
+        let expected = plugin.count_triads_sequential();
+        assert!(expected.total() > 0);
+        assert_eq!(sparse.count_triads(), expected);
+        assert_eq!(plugin.count_triads_optimized(), expected);
+        assert_eq!(SparseSigns::from_plugin(&plugin).count_triads(), expected);
+        // The plugin's threshold, not the standalone default, picks parallel
+        plugin.set_parallel_threshold(10);
+        assert_eq!(plugin.count_triads_optimized(), expected);
+        assert_eq!(sparse.count_triads_with(true), expected);
+        assert_eq!(
+            sparse.edge_count(),
+            plugin.network_summary().edges() as usize
+        );
+        assert_eq!(sparse.sign(9, 0), -1);
+        assert_eq!(sparse.sign(4, 0), 0);
+
+        // Duplicates keep the last edge, self-loops and zeros are dropped
+        let sparse = SparseSigns::from_edges(3, [(0, 1, 1), (1, 0, -1), (2, 2, 1), (1, 2, 0)]);
+        assert_eq!(sparse.edge_count(), 1);
+        assert_eq!(sparse.sign(0, 1), -1);
+        assert_eq!(sparse.labels(), &["Node0", "Node1", "Node2"]);
+    }
+
+    #[test]
+    fn test_parallel_threshold() {
+        let matrix = vec![vec![1.0; 20]; 20];
+        let mut plugin = TriadCounterPlugin::from_matrix(matrix);
+        assert_eq!(plugin.parallel_threshold(), 500);
+        assert_eq!(plugin.counting_strategy(), CountingStrategy::Sequential);
+        let sequential = plugin.count_triads_optimized();
+
+        plugin.set_parallel_threshold(20);
+        let expected = if cfg!(feature = "parallel") {
+            CountingStrategy::Parallel
+        } else {
+            CountingStrategy::Sequential
+        };
+        assert_eq!(plugin.counting_strategy(), expected);
+        assert_eq!(plugin.count_triads_optimized(), sequential);
+
+        plugin.set_parallel_threshold(21);
+        assert_eq!(plugin.counting_strategy(), CountingStrategy::Sequential);
+    }
+
+    #[test]
+    fn test_weighted_balance_sum() {
+        // {0,1,2}: 2 * 0.5 * 3 = 3; {0,1,3}: 2 * -1 * -4 = 8;
+        // {0,2,3}: 0.5 * -1 * 0.25 = -0.125; {1,2,3}: 3 * -4 * 0.25 = -3
+        let matrix = vec![
+            vec![0.0, 2.0, 0.5, -1.0],
+            vec![2.0, 0.0, 3.0, -4.0],
+            vec![0.5, 3.0, 0.0, 0.25],
+            vec![-1.0, -4.0, 0.25, 0.0],
+        ];
+        let plugin = TriadCounterPlugin::from_matrix(matrix);
+        assert_eq!(plugin.weighted_balance_sum(), 7.875);
+
+        // 1e16 + 1 + 1: naive summation drops both unit terms
+        let matrix = vec![
+            vec![0.0, 1e8, 1e8, 1e-8, 1e-8],
+            vec![1e8, 0.0, 1.0, 1.0, 1.0],
+            vec![1e8, 1.0, 0.0, 0.0, 0.0],
+            vec![1e-8, 1.0, 0.0, 0.0, 0.0],
+            vec![1e-8, 1.0, 0.0, 0.0, 0.0],
+        ];
+        let plugin = TriadCounterPlugin::from_matrix(matrix);
+        assert_eq!(plugin.weighted_balance_sum(), 1e16 + 2.0);
+    }
+
+    #[test]
+    fn test_node_weighted_counts() {
+        // All positive 4-node clique: triads {0,1,2}, {0,1,3}, {0,2,3}, {1,2,3}
+        let matrix = vec![
+            vec![0.0, 1.0, 1.0, 1.0],
+            vec![1.0, 0.0, 1.0, 1.0],
+            vec![1.0, 1.0, 0.0, 1.0],
+            vec![1.0, 1.0, 1.0, 0.0],
+        ];
+        let plugin = TriadCounterPlugin::from_matrix(matrix);
+        let weighted = plugin.count_triads_node_weighted(&[1.0, 2.0, 3.0, 0.5]);
+
+        // 6 + 1 + 1.5 + 3
+        assert_eq!(weighted.three_positive, 11.5);
+        assert_eq!(weighted.unstable(), 0.0);
+
+        // Unit importance reproduces the plain counts
+        let unit = plugin.count_triads_node_weighted(&[1.0; 4]);
+        assert_eq!(unit.total(), 4.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "importance length")]
+    fn test_node_weighted_length_mismatch() {
+        let plugin = TriadCounterPlugin::from_matrix(vec![vec![0.0; 3]; 3]);
+        plugin.count_triads_node_weighted(&[1.0, 1.0]);
     }
 }
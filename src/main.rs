@@ -1,26 +1,73 @@
 //! TriadCounter CLI - Network triad analysis tool
 //!
-//! Usage: triad-counter <input.csv> <output.txt>
+//! Usage: triad-counter <input.csv> <output> [--format text|json|csv] [--threads <n>]
+//!        [--report <report.json>]
 
+use log::info;
 use std::env;
 use std::process;
+use std::time::Instant;
 use triad_counter_rs::TriadCounterPlugin;
 
-fn main() {
-    let args: Vec<String> = env::args().collect();
-
-    if args.len() != 3 {
-        eprintln!("Usage: {} <input.csv> <output.txt>", args[0]);
-        eprintln!();
-        eprintln!("Analyzes triadic relationships in signed networks.");
-        eprintln!("Input: CSV adjacency matrix with node labels");
-        eprintln!("Output: Triad counts and stability analysis");
-        process::exit(1);
+/// Format of the main output file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputFormat {
+    /// The asterisk-banner summary (`output`)
+    Text,
+    /// Structured counts and labels (`output_json`)
+    Json,
+    /// Per-node counts in input order (`output_per_node_csv`)
+    Csv,
+}
+
+impl OutputFormat {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "text" => Some(OutputFormat::Text),
+            "json" => Some(OutputFormat::Json),
+            "csv" => Some(OutputFormat::Csv),
+            _ => None,
+        }
     }
+}
 
-    let input_file = &args[1];
-    let output_file = &args[2];
+/// Parsed command-line arguments
+struct Args {
+    input_file: String,
+    output_file: String,
+    format: OutputFormat,
+    threads: Option<usize>,
+    report_file: Option<String>,
+}
 
+fn main() {
+    // Progress messages at info level by default; override with RUST_LOG
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+
+    let argv: Vec<String> = env::args().collect();
+    let args = match parse_args(&argv[1..]) {
+        Some(args) => args,
+        None => {
+            eprintln!(
+                "Usage: {} <input.csv> <output> [--format text|json|csv] [--threads <n>] [--report <report.json>]",
+                argv[0]
+            );
+            eprintln!();
+            eprintln!("Analyzes triadic relationships in signed networks.");
+            eprintln!("Input: CSV adjacency matrix with node labels");
+            eprintln!("Output: Triad counts and stability analysis");
+            eprintln!("--format: text (default), json, or csv (per-node counts)");
+            eprintln!(
+                "--threads: Worker threads for counting (default: RAYON_NUM_THREADS or all cores)"
+            );
+            eprintln!("--report: Also write a JSON run report (timing, strategy, counts)");
+            process::exit(1);
+        }
+    };
+    let input_file = &args.input_file;
+    let output_file = &args.output_file;
+
+    let start = Instant::now();
     let mut plugin = TriadCounterPlugin::new();
 
     // Input phase
@@ -29,17 +76,38 @@ fn main() {
         process::exit(1);
     }
 
-    eprintln!(
-        "Loaded network with {} nodes ({} possible triads)",
-        plugin.node_count(),
-        count_triads(plugin.node_count())
+    match plugin.possible_triads() {
+        u64::MAX => info!(
+            "Loaded network with {} nodes (possible triads exceed u64)",
+            plugin.node_count()
+        ),
+        possible => info!(
+            "Loaded network with {} nodes ({} possible triads)",
+            plugin.node_count(),
+            possible
+        ),
+    }
+
+    let estimate = plugin.estimate_work();
+    info!(
+        "Estimated work: {} edges, {} third-node checks ({:.1}% of pairs skipped)",
+        estimate.edges,
+        estimate.inner_checks,
+        100.0 * estimate.skip_rate
     );
 
     // Run phase
-    plugin.run();
-
-    let counts = plugin.counts();
-    eprintln!(
+    let counts = match args.threads {
+        Some(threads) => match plugin.run_in_pool(threads) {
+            Ok(counts) => counts,
+            Err(e) => {
+                eprintln!("Error starting {} worker threads: {}", threads, e);
+                process::exit(1);
+            }
+        },
+        None => plugin.run(),
+    };
+    info!(
         "Found {} triads: {} stable, {} unstable",
         counts.total(),
         counts.stable(),
@@ -47,19 +115,83 @@ fn main() {
     );
 
     // Output phase
-    if let Err(e) = plugin.output(output_file) {
+    let written = match args.format {
+        OutputFormat::Text => plugin.output(output_file),
+        OutputFormat::Json => plugin.output_json(output_file),
+        OutputFormat::Csv => plugin.output_per_node_csv(output_file),
+    };
+    if let Err(e) = written {
         eprintln!("Error writing output file '{}': {}", output_file, e);
         process::exit(1);
     }
 
-    eprintln!("Results written to '{}'", output_file);
+    info!("Results written to '{}'", output_file);
+
+    if let Some(report_file) = &args.report_file {
+        let elapsed = start.elapsed().as_secs_f64();
+        if let Err(e) = plugin.output_run_report(report_file, input_file, elapsed) {
+            eprintln!("Error writing report file '{}': {}", report_file, e);
+            process::exit(1);
+        }
+        info!("Run report written to '{}'", report_file);
+    }
+}
+
+/// Parse `<input> <output> [--format <name>] [--threads <n>] [--report <path>]`,
+/// returning `None` on misuse
+fn parse_args(args: &[String]) -> Option<Args> {
+    let mut positional = Vec::new();
+    let mut format = OutputFormat::Text;
+    let mut threads = None;
+    let mut report_file = None;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--format" => format = OutputFormat::parse(iter.next()?)?,
+            "--threads" => threads = Some(iter.next()?.parse().ok()?),
+            "--report" => report_file = Some(iter.next()?.clone()),
+            _ if arg.starts_with("--") => return None,
+            _ => positional.push(arg.clone()),
+        }
+    }
+
+    if positional.len() != 2 {
+        return None;
+    }
+    let output_file = positional.pop()?;
+    let input_file = positional.pop()?;
+
+    Some(Args {
+        input_file,
+        output_file,
+        format,
+        threads,
+        report_file,
+    })
 }
 
-/// Calculate number of possible triads: C(n, 3) = n! / (3! * (n-3)!)
-fn count_triads(n: usize) -> usize {
-    if n < 3 {
-        0
-    } else {
-        n * (n - 1) * (n - 2) / 6
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_options() {
+        let argv = |args: &[&str]| -> Vec<String> { args.iter().map(|a| a.to_string()).collect() };
+
+        let args = parse_args(&argv(&["in.csv", "out.txt"])).unwrap();
+        assert_eq!(args.format, OutputFormat::Text);
+        let args = parse_args(&argv(&["--format", "json", "in.csv", "out.json"])).unwrap();
+        assert_eq!(args.format, OutputFormat::Json);
+        assert_eq!(args.output_file, "out.json");
+        let args = parse_args(&argv(&["in.csv", "out.csv", "--format", "csv"])).unwrap();
+        assert_eq!(args.format, OutputFormat::Csv);
+
+        assert!(parse_args(&argv(&["in.csv", "out", "--format", "xml"])).is_none());
+        assert!(parse_args(&argv(&["in.csv", "out", "--format"])).is_none());
+
+        let args = parse_args(&argv(&["--threads", "4", "in.csv", "out"])).unwrap();
+        assert_eq!(args.threads, Some(4));
+        assert!(parse_args(&argv(&["in.csv", "out", "--threads", "four"])).is_none());
     }
 }
@@ -0,0 +1,204 @@
+//! Compressed sparse row sign storage for low-density networks
+
+use crate::{sum_rows, TriadCounterPlugin, TriadCounts, TriadType, DEFAULT_PARALLEL_THRESHOLD};
+
+/// Signed network stored as sorted forward neighbor lists
+///
+/// Node `i`'s list holds its neighbors `j > i` in increasing order, with the
+/// sign of each edge, so memory grows with the number of edges rather than
+/// `n²`. Triads are found by intersecting the lists of an edge's two
+/// endpoints, which visits each triangle exactly once, from its smallest node.
+/// Build one with [`from_edges`](Self::from_edges) to avoid ever storing a
+/// dense matrix.
+#[derive(Debug, Clone)]
+pub struct SparseSigns {
+    /// Number of nodes
+    n: usize,
+    /// Node `i`'s entries are `offsets[i]..offsets[i + 1]`
+    offsets: Vec<usize>,
+    /// Forward neighbor of each entry, sorted within each node
+    neighbors: Vec<usize>,
+    /// Sign of each entry, 1 or -1
+    signs: Vec<i8>,
+    /// Node labels
+    labels: Vec<String>,
+}
+
+impl SparseSigns {
+    /// Build from undirected `(u, v, sign)` edges over `n` nodes, named
+    /// `Node0`, `Node1`, ...
+    ///
+    /// Only the sign of each value matters, and 0 means no edge. If a pair
+    /// appears more than once (in either direction) the last edge wins, as in
+    /// [`TriadCounterPlugin::input_edgelist`]. Self-loops are ignored.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an endpoint is not below `n`.
+    pub fn from_edges<I>(n: usize, edges: I) -> Self
+    where
+        I: IntoIterator<Item = (usize, usize, i8)>,
+    {
+        let mut entries: Vec<(usize, usize, i8)> = Vec::new();
+        for (u, v, sign) in edges {
+            assert!(
+                u < n && v < n,
+                "edge ({}, {}) out of range for {} nodes",
+                u,
+                v,
+                n
+            );
+            if u != v {
+                entries.push((u.min(v), u.max(v), sign.signum()));
+            }
+        }
+
+        // Stable sort keeps input order within a pair, so the last is kept
+        entries.sort_by_key(|&(u, v, _)| (u, v));
+        let mut kept: Vec<(usize, usize, i8)> = Vec::with_capacity(entries.len());
+        for entry in entries {
+            match kept.last_mut() {
+                Some(last) if (last.0, last.1) == (entry.0, entry.1) => *last = entry,
+                _ => kept.push(entry),
+            }
+        }
+        kept.retain(|&(_, _, sign)| sign != 0);
+
+        let mut offsets = vec![0; n + 1];
+        for &(u, _, _) in &kept {
+            offsets[u + 1] += 1;
+        }
+        for i in 0..n {
+            offsets[i + 1] += offsets[i];
+        }
+
+        Self {
+            n,
+            offsets,
+            neighbors: kept.iter().map(|&(_, v, _)| v).collect(),
+            signs: kept.iter().map(|&(_, _, sign)| sign).collect(),
+            labels: (0..n).map(|i| format!("Node{}", i)).collect(),
+        }
+    }
+
+    /// Pack the signs of a loaded plugin, keeping its labels
+    ///
+    /// Reads the upper triangle, as the plugin's own counting loop does, so
+    /// [`count_triads`](Self::count_triads) matches
+    /// [`TriadCounterPlugin::count_triads_sequential`] for any matrix.
+    pub fn from_plugin(plugin: &TriadCounterPlugin) -> Self {
+        let n = plugin.n;
+        let mut offsets = Vec::with_capacity(n + 1);
+        let mut neighbors = Vec::new();
+        let mut signs = Vec::new();
+
+        offsets.push(0);
+        for i in 0..n {
+            for j in (i + 1)..n {
+                let sign = plugin.signs[i * n + j];
+                if sign != 0 {
+                    neighbors.push(j);
+                    signs.push(sign);
+                }
+            }
+            offsets.push(neighbors.len());
+        }
+
+        Self {
+            n,
+            offsets,
+            neighbors,
+            signs,
+            labels: plugin.labels.clone(),
+        }
+    }
+
+    /// Number of nodes
+    pub fn node_count(&self) -> usize {
+        self.n
+    }
+
+    /// Node labels
+    pub fn labels(&self) -> &[String] {
+        &self.labels
+    }
+
+    /// Number of stored edges, each undirected pair counted once
+    pub fn edge_count(&self) -> usize {
+        self.neighbors.len()
+    }
+
+    /// Stored edges over possible pairs; 0.0 for fewer than 2 nodes
+    pub fn density(&self) -> f64 {
+        let pairs = self.n * self.n.saturating_sub(1) / 2;
+        if pairs == 0 {
+            0.0
+        } else {
+            self.edge_count() as f64 / pairs as f64
+        }
+    }
+
+    /// Sign of the unordered pair `(a, b)`: 1, -1, or 0 for no edge
+    ///
+    /// # Panics
+    ///
+    /// Panics if either index is out of range.
+    pub fn sign(&self, a: usize, b: usize) -> i8 {
+        assert!(
+            a < self.n && b < self.n,
+            "pair ({}, {}) out of range for {} nodes",
+            a,
+            b,
+            self.n
+        );
+        let (i, j) = (a.min(b), a.max(b));
+        let (neighbors, signs) = self.row(i);
+        match neighbors.binary_search(&j) {
+            Ok(pos) => signs[pos],
+            Err(_) => 0,
+        }
+    }
+
+    /// Forward neighbors of `i` and their signs
+    #[inline(always)]
+    fn row(&self, i: usize) -> (&[usize], &[i8]) {
+        let range = self.offsets[i]..self.offsets[i + 1];
+        (&self.neighbors[range.clone()], &self.signs[range])
+    }
+
+    /// Count triads by category, in parallel from
+    /// [`DEFAULT_PARALLEL_THRESHOLD`] nodes
+    pub fn count_triads(&self) -> TriadCounts {
+        self.count_triads_with(self.n >= DEFAULT_PARALLEL_THRESHOLD)
+    }
+
+    /// Count triads, spreading rows over rayon's pool if `parallel` is set
+    pub(crate) fn count_triads_with(&self, parallel: bool) -> TriadCounts {
+        let count_row = |i: usize| {
+            let mut counts = TriadCounts::default();
+            let (i_nbrs, i_signs) = self.row(i);
+            for (pos, (&j, &ij)) in i_nbrs.iter().zip(i_signs).enumerate() {
+                // Third nodes k > j appear after j in i's list and anywhere in j's
+                let (ik_nbrs, ik_signs) = (&i_nbrs[pos + 1..], &i_signs[pos + 1..]);
+                let (jk_nbrs, jk_signs) = self.row(j);
+                let (mut a, mut b) = (0, 0);
+                while a < ik_nbrs.len() && b < jk_nbrs.len() {
+                    match ik_nbrs[a].cmp(&jk_nbrs[b]) {
+                        std::cmp::Ordering::Less => a += 1,
+                        std::cmp::Ordering::Greater => b += 1,
+                        std::cmp::Ordering::Equal => {
+                            let (ik, jk) = (ik_signs[a], jk_signs[b]);
+                            let pos_count = ((ij > 0) as u8) + ((ik > 0) as u8) + ((jk > 0) as u8);
+                            counts.record(TriadType::from_positive_count(pos_count));
+                            a += 1;
+                            b += 1;
+                        }
+                    }
+                }
+            }
+            counts
+        };
+
+        sum_rows(self.n, parallel, count_row)
+    }
+}
@@ -0,0 +1,147 @@
+//! Packed upper-triangular sign storage for symmetric networks
+
+use crate::{
+    sum_rows, TriadCounterPlugin, TriadCounts, TriadError, TriadType, DEFAULT_PARALLEL_THRESHOLD,
+};
+
+/// Sign matrix of a symmetric network, keeping only the `n * (n - 1) / 2`
+/// pairs above the diagonal
+///
+/// Row `i` holds the signs of pairs `(i, j)` for `j > i`, contiguously, so the
+/// triad loop reads the same values as [`TriadCounterPlugin`] with half the
+/// memory. Weights are not kept; build one with
+/// [`from_matrix`](Self::from_matrix) to avoid ever storing the full matrix.
+#[derive(Debug, Clone)]
+pub struct UpperTriangularSigns {
+    /// Number of nodes
+    n: usize,
+    /// Packed row-major upper triangle
+    signs: Vec<i8>,
+    /// Node labels
+    labels: Vec<String>,
+}
+
+impl UpperTriangularSigns {
+    /// Pack a symmetric nested matrix, with nodes named `Node0`, `Node1`, ...
+    ///
+    /// Fails with [`TriadError::AsymmetricMatrix`] at the first pair whose two
+    /// directions differ; the diagonal is ignored.
+    pub fn from_matrix(matrix: Vec<Vec<f64>>) -> Result<Self, TriadError> {
+        let n = matrix.len();
+        let at = |i: usize, j: usize| matrix[i].get(j).copied().unwrap_or(0.0);
+
+        let mut signs = Vec::with_capacity(n * n.saturating_sub(1) / 2);
+        for i in 0..n {
+            for j in (i + 1)..n {
+                let (upper, lower) = (at(i, j), at(j, i));
+                if upper != lower {
+                    return Err(TriadError::AsymmetricMatrix {
+                        row: i,
+                        col: j,
+                        upper,
+                        lower,
+                    });
+                }
+                signs.push(TriadCounterPlugin::to_sign(upper));
+            }
+        }
+
+        Ok(Self {
+            n,
+            signs,
+            labels: (0..n).map(|i| format!("Node{}", i)).collect(),
+        })
+    }
+
+    /// Pack the signs of a loaded plugin, keeping its labels
+    ///
+    /// Fails with [`TriadError::AsymmetricMatrix`] unless the plugin's matrix
+    /// is symmetric (see [`TriadCounterPlugin::is_symmetric`]).
+    pub fn from_plugin(plugin: &TriadCounterPlugin) -> Result<Self, TriadError> {
+        plugin.check_symmetric()?;
+
+        let n = plugin.n;
+        let mut signs = Vec::with_capacity(n * n.saturating_sub(1) / 2);
+        for i in 0..n {
+            signs.extend_from_slice(&plugin.signs[i * n + i + 1..(i + 1) * n]);
+        }
+
+        Ok(Self {
+            n,
+            signs,
+            labels: plugin.labels.clone(),
+        })
+    }
+
+    /// Number of nodes
+    pub fn node_count(&self) -> usize {
+        self.n
+    }
+
+    /// Node labels
+    pub fn labels(&self) -> &[String] {
+        &self.labels
+    }
+
+    /// Number of stored pairs, `n * (n - 1) / 2`
+    pub fn len(&self) -> usize {
+        self.signs.len()
+    }
+
+    /// Whether no pairs are stored (fewer than 2 nodes)
+    pub fn is_empty(&self) -> bool {
+        self.signs.is_empty()
+    }
+
+    /// Sign of the unordered pair `(a, b)`: 1, -1, or 0 for no edge
+    ///
+    /// # Panics
+    ///
+    /// Panics if either index is out of range or `a == b`.
+    pub fn sign(&self, a: usize, b: usize) -> i8 {
+        assert!(
+            a < self.n && b < self.n,
+            "pair ({}, {}) out of range for {} nodes",
+            a,
+            b,
+            self.n
+        );
+        assert_ne!(a, b, "the diagonal is not stored");
+        let (i, j) = (a.min(b), a.max(b));
+        self.row(i)[j - i - 1]
+    }
+
+    /// Signs of pairs `(i, j)` for `j > i`
+    #[inline(always)]
+    fn row(&self, i: usize) -> &[i8] {
+        // Rows before `i` hold (n - 1) + (n - 2) + ... + (n - i) pairs
+        let start = i * (2 * self.n - i - 1) / 2;
+        &self.signs[start..start + (self.n - i - 1)]
+    }
+
+    /// Count triads by category, in parallel from
+    /// [`DEFAULT_PARALLEL_THRESHOLD`] nodes
+    pub fn count_triads(&self) -> TriadCounts {
+        let count_row = |i: usize| {
+            let mut counts = TriadCounts::default();
+            let row_i = self.row(i);
+            for (offset, &ij) in row_i.iter().enumerate() {
+                if ij == 0 {
+                    continue;
+                }
+                let j = i + 1 + offset;
+                // Both slices start at k = j + 1
+                for (&ik, &jk) in row_i[offset + 1..].iter().zip(self.row(j)) {
+                    if ik == 0 || jk == 0 {
+                        continue;
+                    }
+                    let pos_count = ((ij > 0) as u8) + ((ik > 0) as u8) + ((jk > 0) as u8);
+                    counts.record(TriadType::from_positive_count(pos_count));
+                }
+            }
+            counts
+        };
+
+        sum_rows(self.n, self.n >= DEFAULT_PARALLEL_THRESHOLD, count_row)
+    }
+}